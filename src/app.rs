@@ -1,13 +1,98 @@
-use std::path::Path;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt::Write as _,
+    io::{Cursor, Read},
+    path::Path,
+    sync::atomic::Ordering,
+};
 
+use anyhow::{bail, Context};
 use chip8::{graphics::Rgb, Chip8};
 use eframe::Frame;
+use egui::Color32;
+use flate2::read::GzDecoder;
+use image::{imageops::FilterType, ImageOutputFormat, RgbImage};
 
-#[cfg(not(target_arch = "wasm32"))]
+use crate::assembler;
 use crate::audio;
-use crate::gui::{Chip8Message, Gui};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::cli::Args;
+use crate::disassembler::{self, MEMORY_SIZE, PROGRAM_START};
+use crate::gui::{AppState, Chip8Message, DrawStats, Gui, Performance, RecentRom};
+use crate::recorder::Recorder;
+use crate::replay::{InputRecorder, Replay, ReplayPlayer};
+use crate::save_state;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::trace::Tracer;
+
+pub const DEFAULT_CYCLES_PER_SECOND: u32 = 600;
+
+/// The rate `Chip8Message::SetStepRate` assumes when converting steps per frame
+/// to cycles per second.
+const LEGACY_FRAME_RATE: u32 = 60;
+
+/// The longest frame time that is caught up on, in seconds. This stops the
+/// `Chip8` from running a huge burst of cycles after the app was suspended.
+const MAX_FRAME_TIME: f32 = 0.25;
+
+/// The default amount of frames kept in the rewind buffer, which is 10 seconds at 60 FPS.
+///
+/// Each snapshot is a serialized [`Chip8`], which takes roughly 10-20KB depending
+/// on the instruction history, so the default buffer uses around 6-12MB.
+pub const DEFAULT_REWIND_FRAMES: usize = 600;
+
+/// The factor screenshots are upscaled by, so that each `Chip8` pixel
+/// is clearly visible in the exported image.
+const SCREENSHOT_SCALE: u32 = 8;
+
+/// The maximum amount of cycles run by a single step over or step out, so that
+/// a subroutine that never returns doesn't hang the app.
+const MAX_STEP_CYCLES: u32 = 1_000_000;
+
+/// The bytes every gzip-compressed file starts with.
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
 
-pub const DEFAULT_STEPS_PER_FRAME: u32 = 10;
+/// The maximum amount of ROMs kept in the recently opened list.
+const MAX_RECENT_ROMS: usize = 10;
+
+/// Logged when an input recording is played back for a ROM that draws random
+/// numbers (`Cxkk`), which can't be seeded and so aren't reproduced.
+const RANDOM_REPLAY_WARNING: &str = "The ROM draws random numbers, which input recordings \
+    don't reproduce, so the playback can desync.";
+
+/// How often the performance overlay is updated, in seconds.
+const PERFORMANCE_SAMPLE_TIME: f64 = 0.5;
+
+/// What to do when the program counter reaches a word that isn't a valid
+/// instruction, such as the zeroed memory after a program that ran off its end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum InvalidOpcodePolicy {
+    /// Execute it anyway, which the `Chip8` treats as doing nothing.
+    #[default]
+    Continue,
+    /// Pause before executing it, like a breakpoint.
+    Halt,
+    /// Log a warning, then execute it anyway.
+    Log,
+}
+
+impl InvalidOpcodePolicy {
+    /// Every available [`InvalidOpcodePolicy`], in the order they are displayed.
+    pub const ALL: [Self; 3] = [Self::Continue, Self::Halt, Self::Log];
+}
+
+impl std::fmt::Display for InvalidOpcodePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Continue => "Continue",
+            Self::Halt => "Halt",
+            Self::Log => "Log",
+        };
+        f.write_str(name)
+    }
+}
 
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -16,26 +101,117 @@ pub struct App {
     #[serde(skip)]
     chip8: Chip8,
     gui: Gui,
-    #[cfg(not(target_arch = "wasm32"))]
+    /// The running audio system, or `None` if audio is unavailable, in which
+    /// case the app runs silently.
+    #[serde(skip)]
+    audio: Option<audio::System>,
+    audio_settings: audio::Settings,
+    cycles_per_second: u32,
+    /// The fraction of a cycle carried over from the previous frame.
     #[serde(skip)]
-    audio: audio::System,
-    steps_per_frame: u32,
+    cycle_accumulator: f64,
     paused: bool,
+    /// Pause whenever a ROM is loaded or reset, so breakpoints can be set
+    /// before its first instruction runs.
+    start_paused: bool,
+    /// Set when execution is resumed, so that the breakpoint at the current
+    /// program counter is skipped instead of pausing again straight away.
+    #[serde(skip)]
+    resumed: bool,
+    breakpoints: BTreeSet<usize>,
+    invalid_opcode_policy: InvalidOpcodePolicy,
     last_rom: Vec<u8>,
+    /// The CRC32 hash of `last_rom`, computed whenever a ROM is loaded.
+    #[serde(skip)]
+    rom_hash: u32,
+    /// The most recently opened ROMs, newest first.
+    recent_roms: Vec<RecentRom>,
+    /// Serialized [`Chip8`] snapshots of the most recent frames, newest last.
+    #[serde(skip)]
+    rewind_buffer: VecDeque<Vec<u8>>,
+    rewind_capacity: usize,
+    /// Refuse to load save states made with a different ROM, instead of only warning.
+    reject_mismatched_states: bool,
+    /// The display colors, applied again whenever the [`Chip8`] is reset or
+    /// replaced so that they never revert to the defaults of the new state.
+    foreground_color: Rgb,
+    background_color: Rgb,
+    /// Set when a rewind was requested, so the next frame restores a snapshot
+    /// instead of stepping forward.
+    #[serde(skip)]
+    rewinding: bool,
+    #[serde(skip)]
+    recording: Option<Recorder>,
+    #[serde(skip)]
+    last_recording: Option<Recorder>,
+    /// The amount of cycles executed since the ROM was last restarted, used
+    /// to time the key changes of input recordings.
+    #[serde(skip)]
+    cycle: u64,
+    /// The amount of instructions executed since the ROM was loaded. Unlike
+    /// `cycle`, this doesn't advance while waiting for a key press (Fx0A).
+    #[serde(skip)]
+    instruction_count: u64,
+    #[serde(skip)]
+    input_recording: Option<InputRecorder>,
+    #[serde(skip)]
+    last_input_recording: Option<Replay>,
+    #[serde(skip)]
+    replay: Option<ReplayPlayer>,
+    /// Writes every executed instruction to a file while a trace is running.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    tracer: Option<Tracer>,
+    #[serde(skip)]
+    performance: PerformanceCounter,
+    /// The sprite draws counted since the last frame that ran the [`Chip8`].
+    #[serde(skip)]
+    frame_draw_stats: DrawStats,
+    /// The sprite draws of the last frame that ran the [`Chip8`].
+    #[serde(skip)]
+    draw_stats: DrawStats,
+    /// The errors of the last Octo source that failed to assemble.
+    #[serde(skip)]
+    assembly_errors: Vec<assembler::Error>,
 }
 
 impl Default for App {
     fn default() -> Self {
         let chip8 = Chip8::new();
-        #[cfg(not(target_arch = "wasm32"))]
-        let audio = Self::create_audio_system(&chip8).expect("Failed to create audio::System");
+        let audio = Self::create_audio_system(&chip8, audio::Settings::default());
         Self {
             chip8,
-            #[cfg(not(target_arch = "wasm32"))]
             audio,
-            steps_per_frame: DEFAULT_STEPS_PER_FRAME,
+            audio_settings: audio::Settings::default(),
+            cycles_per_second: DEFAULT_CYCLES_PER_SECOND,
+            cycle_accumulator: 0.0,
             paused: false,
+            start_paused: false,
+            resumed: false,
+            breakpoints: BTreeSet::default(),
+            invalid_opcode_policy: InvalidOpcodePolicy::default(),
             last_rom: Vec::default(),
+            rom_hash: crc32fast::hash(&[]),
+            recent_roms: Vec::default(),
+            rewind_buffer: VecDeque::default(),
+            rewind_capacity: DEFAULT_REWIND_FRAMES,
+            reject_mismatched_states: false,
+            foreground_color: chip8::graphics::DEFAULT_FOREGROUND,
+            background_color: chip8::graphics::DEFAULT_BACKGROUND,
+            rewinding: false,
+            recording: None,
+            last_recording: None,
+            cycle: 0,
+            instruction_count: 0,
+            input_recording: None,
+            last_input_recording: None,
+            replay: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            tracer: None,
+            performance: PerformanceCounter::default(),
+            frame_draw_stats: DrawStats::default(),
+            draw_stats: DrawStats::default(),
+            assembly_errors: Vec::new(),
             gui: Gui::default(),
         }
     }
@@ -54,12 +230,28 @@ impl eframe::App for App {
 
         egui::CentralPanel::default().show(ctx, |_| {});
 
-        if !self.paused {
-            for _ in 0..self.steps_per_frame {
-                self.chip8.step();
-            }
+        if self.rewinding {
+            self.rewinding = false;
+            self.rewind();
+        } else if !self.paused {
+            self.push_rewind_snapshot();
+            let dt = ctx.input(|input| input.unstable_dt).min(MAX_FRAME_TIME);
+            let cycles = self.cycles_for_frame(dt);
+            self.run(cycles);
+            self.draw_stats = std::mem::take(&mut self.frame_draw_stats);
+        }
+
+        if let Some(recording) = &mut self.recording {
+            let dt = ctx.input(|input| input.stable_dt);
+            recording.capture(dt, &self.chip8.bus.graphics.as_rgb8());
         }
 
+        let time = ctx.input(|input| input.time);
+        self.performance.update(time, self.cycle);
+
+        #[cfg(target_arch = "wasm32")]
+        self.update_web_audio(ctx);
+
         self.update_gui(ctx, frame);
 
         ctx.request_repaint();
@@ -75,143 +267,1212 @@ impl App {
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            return eframe::get_value::<App>(storage, eframe::APP_KEY).unwrap_or_default();
+            let mut app = eframe::get_value::<App>(storage, eframe::APP_KEY).unwrap_or_default();
+            app.rom_hash = crc32fast::hash(&app.last_rom);
+            app.apply_colors();
+            app.apply_audio_settings();
+            #[cfg(target_arch = "wasm32")]
+            app.load_query_rom();
+            return app;
         }
 
-        let mut chip8 = Chip8::new();
-        let mut last_rom = Vec::new();
-
-        if let Some(data) = Self::get_arg_rom() {
-            chip8.load_rom_data(data.clone());
-            last_rom = data;
-        }
+        let chip8 = Chip8::new();
+        let last_rom = Vec::new();
 
-        #[cfg(not(target_arch = "wasm32"))]
-        let audio = Self::create_audio_system(&chip8).expect("Failed to create audio::System");
+        let audio = Self::create_audio_system(&chip8, audio::Settings::default());
 
         let gui = Gui::new();
 
-        Self {
+        let app = Self {
             chip8,
-            #[cfg(not(target_arch = "wasm32"))]
             audio,
-            steps_per_frame: DEFAULT_STEPS_PER_FRAME,
+            audio_settings: audio::Settings::default(),
+            cycles_per_second: DEFAULT_CYCLES_PER_SECOND,
+            cycle_accumulator: 0.0,
             paused: false,
+            start_paused: false,
+            resumed: false,
+            breakpoints: BTreeSet::default(),
+            invalid_opcode_policy: InvalidOpcodePolicy::default(),
+            rom_hash: crc32fast::hash(&last_rom),
             last_rom,
+            recent_roms: Vec::default(),
+            rewind_buffer: VecDeque::default(),
+            rewind_capacity: DEFAULT_REWIND_FRAMES,
+            reject_mismatched_states: false,
+            foreground_color: chip8::graphics::DEFAULT_FOREGROUND,
+            background_color: chip8::graphics::DEFAULT_BACKGROUND,
+            rewinding: false,
+            recording: None,
+            last_recording: None,
+            cycle: 0,
+            instruction_count: 0,
+            input_recording: None,
+            last_input_recording: None,
+            replay: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            tracer: None,
+            performance: PerformanceCounter::default(),
+            frame_draw_stats: DrawStats::default(),
+            draw_stats: DrawStats::default(),
+            assembly_errors: Vec::new(),
             gui,
+        };
+        #[cfg(target_arch = "wasm32")]
+        app.load_query_rom();
+        app
+    }
+
+    /// Load the ROM given by the `rom` query parameter of the page URL, if any,
+    /// such as `?rom=https://example.com/pong.ch8`.
+    #[cfg(target_arch = "wasm32")]
+    fn load_query_rom(&self) {
+        if let Some(url) = crate::web::query_param("rom") {
+            crate::gui::load_rom_from_url(url, self.gui.message_channel.0.clone());
         }
     }
 
+    /// Create the app like [`App::new`], then apply the command line `args` on top
+    /// of the restored state before the first frame.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[must_use]
+    pub fn with_args(cc: &eframe::CreationContext<'_>, args: &Args) -> Self {
+        let mut app = Self::new(cc);
+
+        if let Some(path) = &args.rom {
+            match std::fs::read(path)
+                .map_err(anyhow::Error::from)
+                .and_then(decode_rom)
+            {
+                Ok(data) => app.load_rom(data),
+                Err(e) => log::error!("Failed to read ROM from {}: {e}", path.display()),
+            }
+        }
+        if let Some(speed) = args.speed {
+            app.cycles_per_second = speed;
+        }
+        if args.shift_quirk {
+            app.chip8.processor.shift_quirk_enabled = true;
+        }
+        if args.vblank_wait {
+            app.chip8.processor.vblank_wait = true;
+        }
+        if let Some(color) = args.fg {
+            app.foreground_color = rgb(color);
+        }
+        if let Some(color) = args.bg {
+            app.background_color = rgb(color);
+        }
+        app.apply_colors();
+        app.paused |= args.paused;
+
+        app.gui.apply_args(args);
+        app
+    }
+
     /// Create a new [`audio::System`] using the sound timer from the given
-    /// `Chip8` instance.
+    /// `Chip8` instance, playing the tone described by `settings`.
     ///
-    /// This will also start the audio stream. This function will only return
-    /// the [`audio::System`] if it can be both created and played without errors,
-    /// otherwise it returns `Err`.
-    #[cfg(not(target_arch = "wasm32"))]
-    fn create_audio_system(chip8: &Chip8) -> Result<audio::System, anyhow::Error> {
-        let audio = audio::System::new(chip8.bus.clock.sound_timer.clone())?;
-        audio.play().map(|_| audio).map_err(|e| {
-            log::error!("Failed to play audio stream: {e}");
-            e
-        })
+    /// This will also start the audio stream. If the [`audio::System`] can't be
+    /// both created and played, for example because there is no output device,
+    /// the error is logged and `None` is returned so the app can run silently.
+    fn create_audio_system(chip8: &Chip8, settings: audio::Settings) -> Option<audio::System> {
+        let result = audio::System::new(chip8.bus.clock.sound_timer.clone(), settings)
+            .and_then(|audio| audio.play().map(|()| audio));
+        result
+            .map_err(|e| log::error!("Audio is unavailable, running without sound: {e}"))
+            .ok()
     }
 
     /// Update the [`Gui`] and handle all state-changing messages.
     fn update_gui(&mut self, ctx: &egui::Context, frame: &mut Frame) {
-        self.gui.update(ctx, frame, &self.chip8);
+        let state = AppState {
+            chip8: &self.chip8,
+            paused: self.paused,
+            breakpoints: &self.breakpoints,
+            recent_roms: &self.recent_roms,
+            instruction_count: self.instruction_count,
+            rom_size: self.rom_size(),
+            rom_hash: self.rom_hash,
+            audio_available: self.audio.is_some(),
+            halted: self.is_halted(),
+            can_step_back: !self.rewind_buffer.is_empty(),
+            draw_stats: self.draw_stats,
+            performance: self.performance.performance,
+            assembly_errors: &self.assembly_errors,
+            #[cfg(not(target_arch = "wasm32"))]
+            tracing: self.tracer.is_some(),
+            input_recording: self.input_recording.is_some(),
+            replay_uses_random: self
+                .replay
+                .as_ref()
+                .is_some_and(|replay| replay.replay().uses_random),
+        };
+        self.gui.update(ctx, frame, &state);
 
-        if let Ok(message) = self.gui.message_channel.1.try_recv() {
+        while let Ok(message) = self.gui.message_channel.1.try_recv() {
             match message {
-                Chip8Message::LoadRom(data) => {
-                    self.chip8.reset_and_load(data.clone());
-                    self.last_rom = data;
-                    #[cfg(not(target_arch = "wasm32"))]
-                    self.reset_audio();
+                Chip8Message::LoadRom(data) => match decode_rom(data) {
+                    Ok(data) => self.load_rom(data),
+                    Err(e) => log::error!("Failed to load ROM: {e}"),
+                },
+                Chip8Message::LoadSource(source) => match assembler::assemble(&source) {
+                    Ok(data) => {
+                        self.assembly_errors.clear();
+                        match validate_rom(&data) {
+                            Ok(()) => self.load_rom(data),
+                            Err(e) => log::error!("Failed to load the assembled ROM: {e}"),
+                        }
+                    }
+                    Err(errors) => {
+                        log::error!("Failed to assemble the ROM, found {} errors.", errors.len());
+                        self.assembly_errors = errors;
+                    }
+                },
+                Chip8Message::ClearAssemblyErrors => self.assembly_errors.clear(),
+                Chip8Message::AddRecentRom(rom) => {
+                    self.recent_roms.retain(|recent| *recent != rom);
+                    self.recent_roms.insert(0, rom);
+                    self.recent_roms.truncate(MAX_RECENT_ROMS);
+                }
+                Chip8Message::ColdReset => {
+                    self.cold_reset();
+                    self.pause_if_start_paused();
                 }
                 Chip8Message::ResetROM => {
-                    self.chip8.reset_and_load(self.last_rom.clone());
-                    #[cfg(not(target_arch = "wasm32"))]
-                    self.reset_audio();
+                    self.restart_rom();
+                    self.pause_if_start_paused();
                 }
                 Chip8Message::SetForegroundColor(color) => {
-                    self.chip8.bus.graphics.set_foreground_color(Rgb {
-                        red: color.r(),
-                        green: color.g(),
-                        blue: color.b(),
-                    });
+                    self.foreground_color = rgb(color);
+                    self.apply_colors();
                 }
                 Chip8Message::SetBackgroundColor(color) => {
-                    self.chip8.bus.graphics.set_background_color(Rgb {
-                        red: color.r(),
-                        green: color.g(),
-                        blue: color.b(),
-                    });
+                    self.background_color = rgb(color);
+                    self.apply_colors();
+                }
+                Chip8Message::SetStepRate(steps) => {
+                    self.cycles_per_second = steps.saturating_mul(LEGACY_FRAME_RATE);
+                }
+                Chip8Message::SetCyclesPerSecond(cycles) => self.cycles_per_second = cycles,
+                Chip8Message::SetInvalidOpcodePolicy(policy) => {
+                    self.invalid_opcode_policy = policy;
+                }
+                Chip8Message::SetBeepFrequency(frequency) => {
+                    self.audio_settings.frequency = frequency;
+                    self.apply_audio_settings();
+                }
+                Chip8Message::SetWaveform(waveform) => {
+                    self.audio_settings.waveform = waveform;
+                    self.apply_audio_settings();
+                }
+                Chip8Message::SetVolume(volume) => {
+                    self.audio_settings.volume = volume;
+                    self.apply_audio_settings();
+                }
+                Chip8Message::SetMuted(muted) => {
+                    self.audio_settings.muted = muted;
+                    self.apply_audio_settings();
                 }
-                Chip8Message::SetStepRate(steps) => self.steps_per_frame = steps,
                 Chip8Message::SetShiftQuirk(enabled) => {
                     self.chip8.processor.shift_quirk_enabled = enabled;
                 }
                 Chip8Message::SetVblankWait(enabled) => {
                     self.chip8.processor.vblank_wait = enabled;
                 }
+                // the replay controls the keys while it is playing
+                Chip8Message::UpdateKeys(_) if self.replay.is_some() => {}
                 Chip8Message::UpdateKeys(key_updates) => {
+                    if let Some(recording) = &mut self.input_recording {
+                        recording.record(self.cycle, &key_updates);
+                    }
                     for (key_code, pressed) in key_updates {
                         self.chip8.update_key_state(key_code, pressed);
                     }
                 }
-                Chip8Message::TogglePause => self.paused = !self.paused,
+                Chip8Message::TogglePause => {
+                    self.paused = !self.paused;
+                    self.resumed = !self.paused;
+                }
                 Chip8Message::SaveState(path) => {
                     if let Err(e) = self.save_chip8(&path) {
                         log::error!("Failed to save Chip8 state to {}: {e}.", path.display());
                     }
                 }
-                Chip8Message::LoadState(path) => match Self::load_chip8(&path) {
-                    Ok(chip8) => {
-                        self.chip8 = chip8;
-                        #[cfg(not(target_arch = "wasm32"))]
-                        self.reset_audio();
-                    }
+                Chip8Message::LoadState(path) => match self.load_chip8(&path) {
+                    Ok(state) => self.restore_chip8(state),
                     Err(e) => {
                         log::error!("Failed to load Chip8 state from {}: {e}.", path.display());
                     }
                 },
-                Chip8Message::Step => self.chip8.step(),
+                Chip8Message::SaveStateSlot(slot) => match self.write_slot(frame, slot) {
+                    Ok(()) => log::info!("Saved state to slot {slot}."),
+                    Err(e) => log::error!("Failed to save state to slot {slot}: {e}."),
+                },
+                Chip8Message::LoadStateSlot(slot) => match self.read_slot(frame, slot) {
+                    Ok(state) => {
+                        self.restore_chip8(state);
+                        log::info!("Loaded state from slot {slot}.");
+                    }
+                    Err(e) => log::error!("Failed to load state from slot {slot}: {e}."),
+                },
+                Chip8Message::Step => self.step_n(1),
+                Chip8Message::StepN(steps) => self.step_n(steps),
+                Chip8Message::StepOver => self.step_over(),
+                Chip8Message::StepOut => self.step_out(),
+                Chip8Message::RunToAddress(address) => self.run_to(address),
+                Chip8Message::StartRecording(options) => {
+                    self.recording = Some(Recorder::new(options));
+                }
+                Chip8Message::StopRecording => self.last_recording = self.recording.take(),
+                #[cfg(not(target_arch = "wasm32"))]
+                Chip8Message::StartTrace(path) => match Tracer::create(&path) {
+                    Ok(tracer) => {
+                        self.tracer = Some(tracer);
+                        log::info!("Tracing executed instructions to {}.", path.display());
+                    }
+                    Err(e) => log::error!("Failed to create trace file {}: {e}.", path.display()),
+                },
+                #[cfg(not(target_arch = "wasm32"))]
+                Chip8Message::StopTrace => self.stop_trace(),
+                Chip8Message::SaveRecording(path) => {
+                    if let Some(recording) = self.last_recording.take() {
+                        recording.save(path);
+                    }
+                }
+                Chip8Message::StartInputRecording => {
+                    self.restart_rom();
+                    self.input_recording = Some(InputRecorder::new(self.rom_hash()));
+                }
+                Chip8Message::StopInputRecording => {
+                    self.last_input_recording =
+                        self.input_recording.take().map(InputRecorder::finish);
+                }
+                Chip8Message::SaveInputRecording(path) => {
+                    if let Some(replay) = self.last_input_recording.take() {
+                        let result = replay.to_json().and_then(|bytes| write_file(&path, &bytes));
+                        if let Err(e) = result {
+                            log::error!(
+                                "Failed to save input recording to {}: {e}.",
+                                path.display()
+                            );
+                        }
+                    }
+                }
+                Chip8Message::PlayInputRecording(data) => match Replay::from_json(&data) {
+                    Ok(replay) => {
+                        if replay.rom_hash != self.rom_hash() {
+                            log::warn!(
+                                "The input recording was made with a different ROM \
+                                ({:08x}, current is {:08x}), playback will likely desync.",
+                                replay.rom_hash,
+                                self.rom_hash()
+                            );
+                        }
+                        if replay.uses_random {
+                            log::warn!("{RANDOM_REPLAY_WARNING}");
+                        }
+                        self.restart_rom();
+                        self.replay = Some(ReplayPlayer::new(replay));
+                    }
+                    Err(e) => log::error!("Failed to load input recording: {e}."),
+                },
+                Chip8Message::AddBreakpoint(address) => {
+                    self.breakpoints.insert(address);
+                }
+                Chip8Message::RemoveBreakpoint(address) => {
+                    self.breakpoints.remove(&address);
+                }
+                Chip8Message::SetRegister { index, value } => {
+                    if let Some(register) = self.chip8.processor.v.get_mut(usize::from(index)) {
+                        *register = value;
+                    }
+                }
+                Chip8Message::SetIndexRegister(i) => {
+                    self.chip8.processor.i = i.min(MEMORY_SIZE - 1);
+                }
+                Chip8Message::WriteMemory { addr, value } => {
+                    if addr < MEMORY_SIZE {
+                        self.chip8.bus.memory[addr] = value;
+                    } else {
+                        log::warn!("Tried to write outside of memory at {addr:#06X}.");
+                    }
+                }
+                Chip8Message::Rewind => self.rewinding = true,
+                Chip8Message::StepBack => self.rewind(),
+                Chip8Message::SetRejectMismatchedStates(reject) => {
+                    self.reject_mismatched_states = reject;
+                }
+                Chip8Message::SetStartPaused(start_paused) => self.start_paused = start_paused,
+                Chip8Message::SetRewindCapacity(capacity) => {
+                    self.rewind_capacity = capacity;
+                    let excess = self.rewind_buffer.len().saturating_sub(capacity);
+                    self.rewind_buffer.drain(..excess);
+                }
+                Chip8Message::SaveScreenshot(path) => {
+                    if let Err(e) = self.save_screenshot(&path) {
+                        log::error!("Failed to save screenshot to {}: {e}.", path.display());
+                    }
+                }
+                Chip8Message::ExportInstructionLog(path) => {
+                    if let Err(e) = self.export_instruction_log(&path) {
+                        log::error!(
+                            "Failed to export instruction log to {}: {e}.",
+                            path.display()
+                        );
+                    }
+                }
+                Chip8Message::CopyStateJson => match self.state_json() {
+                    Ok(json) => {
+                        ctx.output_mut(|output| output.copied_text = json);
+                        log::info!("Copied the state to the clipboard as JSON.");
+                    }
+                    Err(e) => log::error!("Failed to serialize the state as JSON: {e}."),
+                },
+                Chip8Message::CopyScreenAscii { on, off } => match self.screen_ascii(on, off) {
+                    Ok(text) => {
+                        ctx.output_mut(|output| output.copied_text = text);
+                        log::info!("Copied the screen to the clipboard as text.");
+                    }
+                    Err(e) => log::error!("Failed to copy the screen as text: {e}"),
+                },
+            }
+        }
+    }
+
+    /// The amount of cycles to run for a frame that took `dt` seconds, so that the
+    /// [`Chip8`] runs at `cycles_per_second` regardless of the display refresh rate.
+    fn cycles_for_frame(&mut self, dt: f32) -> u32 {
+        self.cycle_accumulator += f64::from(dt) * f64::from(self.cycles_per_second);
+        let cycles = self.cycle_accumulator.floor();
+        self.cycle_accumulator -= cycles;
+        cycles as u32
+    }
+
+    /// Step the [`Chip8`] up to `steps` times. If the program counter reaches a
+    /// breakpoint, or an invalid opcode with [`InvalidOpcodePolicy::Halt`],
+    /// execution is paused before that instruction is executed.
+    fn run(&mut self, steps: u32) {
+        for _ in 0..steps {
+            let resumed = std::mem::take(&mut self.resumed);
+            if !resumed && self.breakpoints.contains(&self.chip8.processor.pc) {
+                self.paused = true;
+                return;
+            }
+            if !resumed && self.check_invalid_opcode() {
+                self.paused = true;
+                return;
+            }
+            self.step();
+        }
+    }
+
+    /// Apply the [`InvalidOpcodePolicy`] to the instruction at the program counter.
+    ///
+    /// Returns `true` if execution should halt before it.
+    fn check_invalid_opcode(&self) -> bool {
+        if self.invalid_opcode_policy == InvalidOpcodePolicy::Continue {
+            return false;
+        }
+        let pc = self.chip8.processor.pc;
+        let Some(opcode) = self.opcode_at(pc) else {
+            return false;
+        };
+        if disassembler::is_instruction(opcode) {
+            return false;
+        }
+
+        match self.invalid_opcode_policy {
+            InvalidOpcodePolicy::Continue => false,
+            InvalidOpcodePolicy::Halt => {
+                log::error!("Halted at invalid opcode {opcode:#06X} at {pc:#05X}.");
+                true
+            }
+            InvalidOpcodePolicy::Log => {
+                log::warn!("Executing invalid opcode {opcode:#06X} at {pc:#05X}.");
+                false
+            }
+        }
+    }
+
+    /// Step the [`Chip8`] up to `steps` times, stopping early at a breakpoint.
+    /// The breakpoint at the current program counter is stepped over.
+    fn step_n(&mut self, steps: u32) {
+        if steps > 0 {
+            self.push_rewind_snapshot();
+            self.resumed = true;
+            self.run(steps);
+        }
+    }
+
+    /// Step the [`Chip8`] once, applying the key changes of the playing replay first.
+    fn step(&mut self) {
+        if let Some(replay) = &mut self.replay {
+            let keys = replay.keys_at(self.cycle);
+            if replay.is_finished() {
+                log::info!("Finished playing back the input recording.");
+                self.replay = None;
+            }
+            if let Some(keys) = keys {
+                for key_code in 0..16 {
+                    self.chip8
+                        .update_key_state(key_code, keys & (1 << key_code) != 0);
+                }
+            }
+        }
+
+        if !self.chip8.bus.input.waiting() {
+            self.instruction_count += 1;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(tracer) = &mut self.tracer {
+                if let Err(e) = tracer.trace(&self.chip8) {
+                    log::error!("Failed to write trace, stopping it: {e}.");
+                    self.tracer = None;
+                }
+            }
+        }
+
+        let pc = self.chip8.processor.pc;
+        let is_draw = pc + 1 < MEMORY_SIZE && self.chip8.bus.memory[pc] >> 4 == 0xD;
+        let is_random = pc + 1 < MEMORY_SIZE && self.chip8.bus.memory[pc] >> 4 == 0xC;
+
+        if is_random {
+            if let Some(recording) = &mut self.input_recording {
+                recording.record_random();
+            }
+            if let Some(replay) = &mut self.replay {
+                if replay.record_random() {
+                    log::warn!("{RANDOM_REPLAY_WARNING}");
+                }
+            }
+        }
+
+        self.chip8.step();
+        self.cycle += 1;
+
+        // a draw that waits for the vertical blank doesn't advance the program counter
+        if is_draw && self.chip8.processor.pc != pc {
+            self.frame_draw_stats.draws += 1;
+            if self.chip8.processor.v[0xF] != 0 {
+                self.frame_draw_stats.collisions += 1;
+            }
+        }
+    }
+
+    /// Stop the running trace, writing the rest of it to its file.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn stop_trace(&mut self) {
+        if let Some(tracer) = self.tracer.take() {
+            match tracer.finish() {
+                Ok(()) => log::info!("Stopped tracing."),
+                Err(e) => log::error!("Failed to finish writing trace: {e}."),
+            }
+        }
+    }
+
+    /// Step the [`Chip8`] once, running any subroutine called by the current
+    /// instruction until it returns.
+    fn step_over(&mut self) {
+        let pc = self.chip8.processor.pc;
+        let is_call = pc + 1 < MEMORY_SIZE && self.chip8.bus.memory[pc] & 0xF0 == 0x20;
+        let sp = self.chip8.processor.sp;
+
+        self.push_rewind_snapshot();
+        self.step();
+        if is_call {
+            self.step_until(|chip8| chip8.processor.sp <= sp);
+        }
+    }
+
+    /// Run the [`Chip8`] until the current subroutine returns.
+    fn step_out(&mut self) {
+        let sp = self.chip8.processor.sp;
+        if sp == 0 {
+            log::warn!("Can't step out, the program is not in a subroutine.");
+            return;
+        }
+        self.push_rewind_snapshot();
+        self.step_until(|chip8| chip8.processor.sp < sp);
+    }
+
+    /// Step the [`Chip8`] until `done` returns `true`, a breakpoint is hit,
+    /// or [`MAX_STEP_CYCLES`] cycles have run. The breakpoint at the current
+    /// program counter is stepped over.
+    ///
+    /// Returns `true` if `done` returned `true`.
+    fn step_until(&mut self, done: impl Fn(&Chip8) -> bool) -> bool {
+        for cycle in 0..MAX_STEP_CYCLES {
+            if done(&self.chip8) {
+                return true;
+            }
+            if cycle > 0 && self.breakpoints.contains(&self.chip8.processor.pc) {
+                return false;
+            }
+            self.step();
+        }
+
+        let done = done(&self.chip8);
+        if !done {
+            log::warn!("Stopped stepping after {MAX_STEP_CYCLES} cycles.");
+        }
+        done
+    }
+
+    /// Run the [`Chip8`] until the program counter reaches `address`, then pause.
+    /// This acts as a one-shot breakpoint, so it stops early at other breakpoints.
+    fn run_to(&mut self, address: usize) {
+        self.paused = true;
+        self.push_rewind_snapshot();
+
+        // step once first, so that running to the current address finds its next visit
+        self.step();
+        if self.step_until(|chip8| chip8.processor.pc == address) {
+            log::info!("Reached {address:#06X}.");
+        } else {
+            let pc = self.chip8.processor.pc;
+            log::warn!("Did not reach {address:#06X}, stopped at {pc:#06X}.");
+        }
+    }
+
+    /// Reload the current ROM from the start, so that an input recording
+    /// can be recorded or played back from a known state.
+    fn restart_rom(&mut self) {
+        self.stop_input_replay();
+        self.chip8.reset_and_load(self.last_rom.clone());
+        self.cycle = 0;
+        self.instruction_count = 0;
+        self.apply_colors();
+        self.reset_audio();
+    }
+
+    /// Reload the current ROM into a brand new [`Chip8`], resetting the quirks
+    /// and colors to their defaults, unlike [`App::restart_rom`].
+    ///
+    /// The rewind buffer is cleared too, as rewinding would bring back the
+    /// quirks and colors from before the reset.
+    fn cold_reset(&mut self) {
+        self.stop_input_replay();
+        self.chip8 = Chip8::new();
+        self.chip8.load_rom_data(self.last_rom.clone());
+        self.foreground_color = chip8::graphics::DEFAULT_FOREGROUND;
+        self.background_color = chip8::graphics::DEFAULT_BACKGROUND;
+        self.rewind_buffer.clear();
+        self.cycle = 0;
+        self.instruction_count = 0;
+        self.reset_audio();
+    }
+
+    /// Serialize the current [`Chip8`] state into the rewind buffer, dropping
+    /// the oldest snapshot if the buffer is full.
+    ///
+    /// This is called once per frame while running, and once before each
+    /// debugger step so that the step can be undone with [`Chip8Message::StepBack`].
+    fn push_rewind_snapshot(&mut self) {
+        if self.rewind_capacity == 0 {
+            return;
+        }
+
+        match save_state::encode(&self.chip8, self.rom_hash) {
+            Ok(snapshot) => {
+                if self.rewind_buffer.len() >= self.rewind_capacity {
+                    self.rewind_buffer.pop_front();
+                }
+                self.rewind_buffer.push_back(snapshot);
             }
+            Err(e) => log::error!("Failed to capture rewind snapshot: {e}"),
+        }
+    }
+
+    /// Restore the most recent snapshot from the rewind buffer, if there is one.
+    fn rewind(&mut self) {
+        let Some(snapshot) = self.rewind_buffer.pop_back() else {
+            return;
+        };
+
+        match save_state::decode(&snapshot) {
+            Ok(state) => self.restore_chip8(state),
+            Err(e) => log::error!("Failed to restore rewind snapshot: {e}"),
         }
     }
 
-    /// Get the ROM data from the path provided as the first argument when
-    /// run from the command line.
-    fn get_arg_rom() -> Option<Vec<u8>> {
-        std::env::args().nth(1).and_then(|rom_path| {
-            std::fs::read(&rom_path)
-                .map_err(|e| log::error!("Failed to read ROM from {rom_path}: {e}"))
-                .ok()
-        })
+    /// Replace the current [`Chip8`] with the one in `state`, keeping the existing
+    /// sound timer so that it stays connected to the running audio system. The
+    /// timer is set to the value stored in `state`.
+    fn restore_chip8(&mut self, state: save_state::SaveState) {
+        self.stop_input_replay();
+        let mut chip8 = state.chip8;
+        let sound_timer = self.chip8.bus.clock.sound_timer.clone();
+        sound_timer.store(state.sound_timer, Ordering::SeqCst);
+        chip8.bus.clock.sound_timer = sound_timer;
+        self.chip8 = chip8;
+        self.apply_colors();
+    }
+
+    /// Replace the current program with the ROM `data`, which must already be
+    /// decoded with [`decode_rom`].
+    fn load_rom(&mut self, data: Vec<u8>) {
+        self.chip8.reset_and_load(data.clone());
+        self.rom_hash = crc32fast::hash(&data);
+        self.last_rom = data;
+        self.stop_input_replay();
+        self.cycle = 0;
+        self.instruction_count = 0;
+        self.apply_colors();
+        self.reset_audio();
+        self.pause_if_start_paused();
+    }
+
+    /// Drop the running input recording and replay. Their cycle numbers only
+    /// line up with the session they started in, so they can't reproduce it
+    /// once the `Chip8` state is replaced.
+    fn stop_input_replay(&mut self) {
+        if self.input_recording.take().is_some() {
+            log::warn!("Stopped the input recording, because the Chip8 state was replaced.");
+        }
+        if self.replay.take().is_some() {
+            log::warn!(
+                "Stopped playing back the input recording, because the Chip8 state was replaced."
+            );
+        }
+    }
+
+    /// Pause execution if the user asked for ROMs to start paused.
+    fn pause_if_start_paused(&mut self) {
+        if self.start_paused {
+            self.paused = true;
+        }
     }
 
     /// Load [`Chip8`] state from the given `path`.
-    fn load_chip8(path: impl AsRef<Path>) -> anyhow::Result<Chip8> {
+    fn load_chip8(&self, path: impl AsRef<Path>) -> anyhow::Result<save_state::SaveState> {
         let bytes = std::fs::read(path)?;
-        let chip8 = bincode::deserialize::<Chip8>(&bytes)?;
-        Ok(chip8)
+        self.check_save_state(save_state::decode(&bytes)?)
+    }
+
+    /// Check that `state` was saved with the currently loaded ROM. A mismatch is
+    /// only logged, unless `reject_mismatched_states` is set.
+    fn check_save_state(
+        &self,
+        state: save_state::SaveState,
+    ) -> anyhow::Result<save_state::SaveState> {
+        match state.rom_hash {
+            Some(hash) if hash != self.rom_hash => {
+                let message = format!(
+                    "The save state was made with a different ROM \
+                    (CRC32 {hash:08x}, the loaded ROM is {:08x}).",
+                    self.rom_hash
+                );
+                if self.reject_mismatched_states {
+                    bail!(message);
+                }
+                log::warn!("{message}");
+            }
+            Some(_) => {}
+            None => log::info!("The save state doesn't record its ROM, it can't be verified."),
+        }
+        Ok(state)
     }
 
     /// Save [`Chip8`] state to a file specified by `path`.
     fn save_chip8(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
-        let bytes = bincode::serialize(&self.chip8)?;
+        let bytes = save_state::encode(&self.chip8, self.rom_hash)?;
         std::fs::write(path, bytes)?;
         Ok(())
     }
 
-    /// Reset the audio system. This should be called anytime the [`Chip8`] is reset,
-    /// as the new sound timer needs to be linked to a new [`audio::System`].
+    /// The CRC32 hash of the currently loaded ROM.
+    #[must_use]
+    pub fn rom_hash(&self) -> u32 {
+        self.rom_hash
+    }
+
+    /// Returns `true` if the program has halted by jumping to itself, which
+    /// programs commonly do once they're finished.
+    #[must_use]
+    pub fn is_halted(&self) -> bool {
+        let pc = self.chip8.processor.pc;
+        self.opcode_at(pc)
+            .is_some_and(|opcode| disassembler::is_self_jump(pc, opcode))
+    }
+
+    /// The two byte opcode at `address`, or `None` if it doesn't fit in memory.
+    fn opcode_at(&self, address: usize) -> Option<usize> {
+        if address + 1 >= MEMORY_SIZE {
+            return None;
+        }
+        let memory = &self.chip8.bus.memory;
+        Some(usize::from(memory[address]) << 8 | usize::from(memory[address + 1]))
+    }
+
+    /// The size of the currently loaded ROM, in bytes.
+    #[must_use]
+    pub fn rom_size(&self) -> usize {
+        self.last_rom.len()
+    }
+
+    /// The path of the given quick save `slot` for the current ROM.
+    ///
+    /// Slots are stored in a directory per ROM, named after the ROM's hash.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn slot_path(&self, slot: u8) -> anyhow::Result<PathBuf> {
+        let dirs = directories_next::ProjectDirs::from("", "", "chip8")
+            .context("Failed to find a data directory.")?;
+        Ok(dirs
+            .data_dir()
+            .join("states")
+            .join(format!("{:08x}", self.rom_hash()))
+            .join(format!("slot{slot}.state")))
+    }
+
+    /// Save [`Chip8`] state to the given quick save `slot`.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn write_slot(&self, _frame: &mut Frame, slot: u8) -> anyhow::Result<()> {
+        let path = self.slot_path(slot)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        self.save_chip8(path)
+    }
+
+    /// Load [`Chip8`] state from the given quick save `slot`.
     #[cfg(not(target_arch = "wasm32"))]
+    fn read_slot(&self, _frame: &mut Frame, slot: u8) -> anyhow::Result<save_state::SaveState> {
+        self.load_chip8(self.slot_path(slot)?)
+    }
+
+    /// The storage key of the given quick save `slot` for the current ROM.
+    #[cfg(target_arch = "wasm32")]
+    fn slot_key(&self, slot: u8) -> String {
+        format!("state_{:08x}_{slot}", self.rom_hash())
+    }
+
+    /// Save [`Chip8`] state to the given quick save `slot`.
+    ///
+    /// There is no file system on the web, so slots are kept in the `eframe` storage.
+    #[cfg(target_arch = "wasm32")]
+    fn write_slot(&self, frame: &mut Frame, slot: u8) -> anyhow::Result<()> {
+        let storage = frame.storage_mut().context("Storage is unavailable.")?;
+        let bytes = save_state::encode(&self.chip8, self.rom_hash)?;
+        eframe::set_value(storage, &self.slot_key(slot), &bytes);
+        Ok(())
+    }
+
+    /// Load [`Chip8`] state from the given quick save `slot`.
+    #[cfg(target_arch = "wasm32")]
+    fn read_slot(&self, frame: &mut Frame, slot: u8) -> anyhow::Result<save_state::SaveState> {
+        let storage = frame.storage().context("Storage is unavailable.")?;
+        let bytes = eframe::get_value::<Vec<u8>>(storage, &self.slot_key(slot))
+            .context("Slot is empty.")?;
+        self.check_save_state(save_state::decode(&bytes)?)
+    }
+
+    /// Save the current display as a PNG to a file specified by `path`,
+    /// upscaled by [`SCREENSHOT_SCALE`] with nearest-neighbor filtering.
+    fn save_screenshot(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let width = chip8::graphics::WIDTH as u32;
+        let height = chip8::graphics::HEIGHT as u32;
+        let image = RgbImage::from_raw(width, height, self.chip8.bus.graphics.as_rgb8().to_vec())
+            .context("Display buffer does not match the screen resolution.")?;
+        let image = image::imageops::resize(
+            &image,
+            width * SCREENSHOT_SCALE,
+            height * SCREENSHOT_SCALE,
+            FilterType::Nearest,
+        );
+
+        let mut bytes = Vec::new();
+        image.write_to(&mut Cursor::new(&mut bytes), ImageOutputFormat::Png)?;
+        write_file(path, &bytes)
+    }
+
+    /// Save the history of executed instructions as CSV to a file specified by `path`,
+    /// oldest first, preceded by a comment with the current program counter.
+    fn export_instruction_log(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let processor = &self.chip8.processor;
+
+        let mut log = format!("# pc: {:#06X}\naddress,opcode,description\n", processor.pc);
+        for instruction in &processor.instructions {
+            let description = instruction.display.replace('"', "\"\"");
+            writeln!(
+                log,
+                "{:#06X},{:#06X},\"{description}\"",
+                instruction.address, instruction.opcode
+            )?;
+        }
+        write_file(path, log.as_bytes())
+    }
+
+    /// Serialize the current [`Chip8`] as pretty JSON, for bug reports and diffing.
+    ///
+    /// The sound timer is shared with the audio system, so it's skipped when the
+    /// `Chip8` is serialized. Its current value is added next to it instead.
+    fn state_json(&self) -> anyhow::Result<String> {
+        #[derive(serde::Serialize)]
+        struct StateJson<'a> {
+            rom_crc32: String,
+            sound_timer: u8,
+            chip8: &'a Chip8,
+        }
+
+        let state = StateJson {
+            rom_crc32: format!("{:08X}", self.rom_hash),
+            sound_timer: self.chip8.bus.clock.sound_timer.load(Ordering::SeqCst),
+            chip8: &self.chip8,
+        };
+        Ok(serde_json::to_string_pretty(&state)?)
+    }
+
+    /// Draw the display as text, one line per row of pixels, preceded by a header
+    /// line with the resolution.
+    ///
+    /// The graphics buffer only exposes the displayed colors, so pixels that have
+    /// the foreground color are drawn as `on`, and every other pixel as `off`.
+    /// This is refused while both colors are the same, since every pixel would
+    /// look like it's on.
+    fn screen_ascii(&self, on: char, off: char) -> anyhow::Result<String> {
+        let width = chip8::graphics::WIDTH;
+        let height = chip8::graphics::HEIGHT;
+        let [foreground, background] = [self.foreground_color, self.background_color]
+            .map(|color| [color.red, color.green, color.blue]);
+        if foreground == background {
+            bail!("The foreground and background colors are the same, pixels can't be told apart.");
+        }
+
+        let mut text = format!("{width}x{height}\n");
+        for row in self.chip8.bus.graphics.as_rgb8().chunks_exact(width * 3) {
+            text.extend(row.chunks_exact(3).map(
+                |pixel| {
+                    if pixel == foreground {
+                        on
+                    } else {
+                        off
+                    }
+                },
+            ));
+            text.push('\n');
+        }
+        Ok(text)
+    }
+
+    /// Apply the configured display colors to the [`Chip8`].
+    fn apply_colors(&mut self) {
+        let graphics = &mut self.chip8.bus.graphics;
+        graphics.set_foreground_color(self.foreground_color);
+        graphics.set_background_color(self.background_color);
+    }
+
+    /// Apply the current [`audio::Settings`] to the running audio system.
+    fn apply_audio_settings(&self) {
+        if let Some(audio) = &self.audio {
+            audio.apply(self.audio_settings);
+        }
+    }
+
+    /// Browsers only allow audio to start in response to a user gesture, so the
+    /// audio system is resumed on any click or key press. There is no audio
+    /// callback on the web, so the tone is also updated from the sound timer here.
+    #[cfg(target_arch = "wasm32")]
+    fn update_web_audio(&self, ctx: &egui::Context) {
+        let Some(audio) = &self.audio else {
+            return;
+        };
+
+        let gesture = ctx.input(|input| {
+            input.pointer.any_pressed()
+                || input
+                    .events
+                    .iter()
+                    .any(|event| matches!(event, egui::Event::Key { pressed: true, .. }))
+        });
+        if gesture {
+            if let Err(e) = audio.play() {
+                log::error!("Failed to resume audio: {e}");
+            }
+        }
+        audio.update();
+    }
+
+    /// Reset the audio system. This should be called anytime the [`Chip8`] is reset,
+    /// as the new sound timer needs to be linked to the [`audio::System`].
+    ///
+    /// On the web, the existing system follows the new timer instead, as a new
+    /// audio context wouldn't play until the next user gesture.
     fn reset_audio(&mut self) {
-        match Self::create_audio_system(&self.chip8) {
-            Ok(audio) => self.audio = audio,
-            Err(e) => log::error!("Failed to create new audio::System: {e}"),
+        #[cfg(target_arch = "wasm32")]
+        if let Some(audio) = &mut self.audio {
+            audio.set_timer(self.chip8.bus.clock.sound_timer.clone());
+            return;
+        }
+        self.audio = Self::create_audio_system(&self.chip8, self.audio_settings);
+    }
+}
+
+/// Measures the frame rate and the speed of the [`Chip8`], averaged over
+/// [`PERFORMANCE_SAMPLE_TIME`] so that the numbers are readable.
+#[derive(Default)]
+struct PerformanceCounter {
+    /// The time the current sample started at.
+    sample_start: f64,
+
+    /// The frames drawn since the current sample started.
+    frames: u32,
+
+    /// The cycle count when the current sample started.
+    start_cycle: u64,
+
+    /// The result of the last complete sample.
+    performance: Performance,
+}
+
+impl PerformanceCounter {
+    /// Count a frame drawn at `time`, with `cycle` being the current cycle count.
+    fn update(&mut self, time: f64, cycle: u64) {
+        self.frames += 1;
+
+        let elapsed = time - self.sample_start;
+        if elapsed < PERFORMANCE_SAMPLE_TIME {
+            return;
         }
+
+        // the cycle count is reset when the ROM is restarted
+        let cycles = cycle.saturating_sub(self.start_cycle);
+        self.performance = Performance {
+            fps: (f64::from(self.frames) / elapsed) as f32,
+            cycles_per_second: (cycles as f64 / elapsed) as f32,
+        };
+
+        self.sample_start = time;
+        self.frames = 0;
+        self.start_cycle = cycle;
+    }
+}
+
+/// Convert an egui color to the color type of the [`Chip8`] graphics.
+fn rgb(color: Color32) -> Rgb {
+    Rgb {
+        red: color.r(),
+        green: color.g(),
+        blue: color.b(),
+    }
+}
+
+/// Decompress `data` if it's gzip-compressed (such as a `.ch8.gz` file), then
+/// check that it fits in memory with [`validate_rom`]. Data that isn't gzip is
+/// treated as a raw ROM.
+fn decode_rom(data: Vec<u8>) -> anyhow::Result<Vec<u8>> {
+    let data = if data.starts_with(&GZIP_MAGIC) {
+        let mut rom = Vec::new();
+        // read one byte past what fits, so that oversized ROMs are still rejected
+        // without decompressing all of them
+        GzDecoder::new(data.as_slice())
+            .take((MEMORY_SIZE - PROGRAM_START + 1) as u64)
+            .read_to_end(&mut rom)
+            .context("Failed to decompress the gzip-compressed ROM.")?;
+        rom
+    } else {
+        data
+    };
+
+    validate_rom(&data)?;
+    Ok(data)
+}
+
+/// Check that `data` fits in the program area of the [`Chip8`] memory.
+///
+/// The `Chip8` itself silently truncates ROMs that are too large, and runs the
+/// zeroed memory of an empty ROM, so both are checked before loading to tell the
+/// user why a ROM doesn't run. ROMs with an odd length are accepted: their
+/// trailing byte is paired with the byte after it, which is zero in a freshly
+/// reset `Chip8`.
+fn validate_rom(data: &[u8]) -> anyhow::Result<()> {
+    let max = MEMORY_SIZE - PROGRAM_START;
+    if data.is_empty() {
+        anyhow::bail!("The ROM is empty.");
+    }
+    if data.len() > max {
+        anyhow::bail!(
+            "The ROM is {} bytes, but at most {max} bytes fit in memory.",
+            data.len()
+        );
+    }
+    Ok(())
+}
+
+/// Write `bytes` to the file at `path`.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn write_file(path: impl AsRef<Path>, bytes: &[u8]) -> anyhow::Result<()> {
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// There is no file system on the web, so this triggers a browser
+/// download named after the file name in `path` instead.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn write_file(path: impl AsRef<Path>, bytes: &[u8]) -> anyhow::Result<()> {
+    let file_name = path
+        .as_ref()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .context("Invalid file name.")?;
+    crate::web::download(file_name, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use chip8::graphics::{DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+
+    /// The red, green and blue channels of `color`.
+    fn channels(color: Rgb) -> (u8, u8, u8) {
+        (color.red, color.green, color.blue)
+    }
+
+    /// An [`App`] with `rom` loaded, as if the user opened it.
+    fn app_with_rom(rom: &[u8]) -> App {
+        let mut app = App::default();
+        app.load_rom(rom.to_vec());
+        app
+    }
+
+    #[test]
+    fn run_stops_at_breakpoint() {
+        let mut app = app_with_rom(&[0x60, 0x01, 0x61, 0x02, 0x62, 0x03, 0x63, 0x04]);
+        app.breakpoints.insert(0x204);
+
+        app.run(10);
+
+        assert_eq!(app.chip8.processor.pc, 0x204);
+        assert!(app.paused);
+        assert_eq!(app.chip8.processor.v[1], 0x02);
+        assert_eq!(app.chip8.processor.v[2], 0x00);
+    }
+
+    #[test]
+    fn only_cold_reset_restores_the_defaults() {
+        let mut app = app_with_rom(&[0x12, 0x00]);
+        let defaults = Chip8::new();
+        let quirk = !defaults.processor.shift_quirk_enabled;
+        // the same changes as the `SetShiftQuirk` and `Set*Color` messages
+        app.chip8.processor.shift_quirk_enabled = quirk;
+        app.foreground_color = rgb(Color32::from_rgb(0x12, 0x34, 0x56));
+        app.background_color = rgb(Color32::from_rgb(0x65, 0x43, 0x21));
+        app.apply_colors();
+        app.push_rewind_snapshot();
+        assert!(!app.rewind_buffer.is_empty());
+
+        app.restart_rom();
+        assert_eq!(app.chip8.processor.shift_quirk_enabled, quirk);
+        assert_eq!(channels(app.foreground_color), (0x12, 0x34, 0x56));
+        assert_eq!(channels(app.background_color), (0x65, 0x43, 0x21));
+
+        app.cold_reset();
+        assert_eq!(app.chip8.processor.shift_quirk_enabled, !quirk);
+        assert_eq!(channels(app.foreground_color), channels(DEFAULT_FOREGROUND));
+        assert_eq!(channels(app.background_color), channels(DEFAULT_BACKGROUND));
+        assert!(app.rewind_buffer.is_empty());
+    }
+
+    #[test]
+    fn validate_rom_checks_the_size() {
+        let max = MEMORY_SIZE - PROGRAM_START;
+        assert_eq!(max, 3584);
+
+        assert!(validate_rom(&vec![0; max]).is_ok());
+        assert!(validate_rom(&vec![0; max + 1]).is_err());
+        assert!(validate_rom(&[]).is_err());
+    }
+
+    #[test]
+    fn self_jump_is_halted() {
+        assert!(app_with_rom(&[0x12, 0x00]).is_halted());
+        assert!(!app_with_rom(&[0x12, 0x02]).is_halted());
+    }
+
+    #[test]
+    fn overlapping_draws_count_a_collision() {
+        // I = sprite, draw it twice at (V0, V0), then halt
+        let mut app = app_with_rom(&[0xA2, 0x08, 0xD0, 0x01, 0xD0, 0x01, 0x12, 0x06, 0xF0]);
+
+        app.run(3);
+
+        assert_eq!(app.frame_draw_stats.draws, 2);
+        assert_eq!(app.frame_draw_stats.collisions, 1);
+    }
+
+    /// Compress `data` like a `.ch8.gz` file.
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn decode_rom_decompresses_gzip() {
+        let rom = vec![0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x12, 0x06];
+        let compressed = gzip(&rom);
+        assert!(compressed.starts_with(&GZIP_MAGIC));
+
+        assert_eq!(decode_rom(compressed).unwrap(), rom);
+        assert_eq!(decode_rom(rom.clone()).unwrap(), rom);
+    }
+
+    #[test]
+    fn decode_rom_rejects_oversized_gzip() {
+        let compressed = gzip(&vec![0; MEMORY_SIZE - PROGRAM_START + 1]);
+
+        assert!(decode_rom(compressed).is_err());
+    }
+
+    #[test]
+    fn screen_ascii_draws_the_screen() {
+        // draw the top row of a 4x4 block at (0, 0)
+        let mut app = app_with_rom(&[0xA2, 0x04, 0xD0, 0x01, 0xF0]);
+        app.run(2);
+
+        let text = app.screen_ascii('#', '.').unwrap();
+        let mut lines = text.lines();
+
+        assert_eq!(lines.next(), Some("64x32"));
+        assert_eq!(
+            lines.next(),
+            Some(format!("####{}", ".".repeat(60)).as_str())
+        );
+        assert_eq!(lines.count(), 31);
+    }
+
+    #[test]
+    fn screen_ascii_refuses_matching_colors() {
+        let mut app = App::default();
+        app.background_color = app.foreground_color;
+
+        assert!(app.screen_ascii('#', ' ').is_err());
+    }
+
+    #[test]
+    fn odd_length_rom_is_padded_with_zero() {
+        let rom = [0x60, 0x01, 0x61];
+        assert!(validate_rom(&rom).is_ok());
+
+        let app = app_with_rom(&rom);
+
+        assert_eq!(app.chip8.bus.memory[PROGRAM_START + 2], 0x61);
+        assert_eq!(app.chip8.bus.memory[PROGRAM_START + 3], 0x00);
+    }
+
+    #[test]
+    fn halts_when_running_off_the_end() {
+        let mut app = app_with_rom(&[0x60, 0x01]);
+        app.invalid_opcode_policy = InvalidOpcodePolicy::Halt;
+
+        app.run(10);
+
+        assert!(app.paused);
+        assert_eq!(app.chip8.processor.pc, 0x202);
+        assert_eq!(app.chip8.processor.v[0], 0x01);
     }
 }
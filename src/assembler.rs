@@ -0,0 +1,740 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::disassembler::PROGRAM_START;
+
+/// Words that have a meaning of their own, so they can't be used as a label.
+const KEYWORDS: &[&str] = &[
+    "clear", "return", "jump", "jump0", "loop", "again", "if", "then", "begin", "else", "end", "i",
+    "delay", "buzzer", "bcd", "save", "load", "sprite", "random", "key", "-key", "hex",
+];
+
+/// An error found while assembling, with the line it was found on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    /// The line of the source the error was found on, starting at 1.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+/// Assemble Octo (`.8o`) `source` into the bytes of a ROM, loaded at [`PROGRAM_START`].
+///
+/// Only a subset of the Octo language is supported: labels (`: name`), calls,
+/// constants (`:const name value`), aliases (`:alias name vX`), the instructions
+/// of the original Chip8, `if ... then`, `if ... begin ... else ... end`,
+/// `loop ... again`, and raw bytes. Macros, `:org`, and the SUPER-CHIP and
+/// XO-CHIP extensions are not supported.
+///
+/// Like Octo, a jump to `main` is inserted at the start of the program if
+/// `main` is defined anywhere else.
+///
+/// # Errors
+///
+/// Returns every error found in the source, in the order of their lines.
+pub fn assemble(source: &str) -> Result<Vec<u8>, Vec<Error>> {
+    let mut assembler = Assembler::new(tokenize(source));
+    assembler.run();
+    assembler.finish()
+}
+
+/// Returns `true` if the file `name` is Octo source, judging by its extension.
+#[must_use]
+pub fn is_source_file(name: &str) -> bool {
+    std::path::Path::new(name)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("8o"))
+}
+
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+}
+
+/// Split `source` into whitespace separated tokens, dropping `#` comments.
+fn tokenize(source: &str) -> Vec<Token<'_>> {
+    source
+        .lines()
+        .enumerate()
+        .flat_map(|(index, line)| {
+            let code = line.split('#').next().unwrap_or_default();
+            code.split_whitespace().map(move |text| Token {
+                text,
+                line: index + 1,
+            })
+        })
+        .collect()
+}
+
+/// An open control flow block, with the line it was opened on.
+enum Block {
+    /// A `loop`, starting at the given address.
+    Loop(usize, usize),
+
+    /// An `if ... begin` or `else`, with the index of the jump past it.
+    If(usize, usize),
+}
+
+/// The right-hand side of a comparison.
+#[derive(Clone, Copy)]
+enum Operand {
+    Register(u8),
+    Byte(u8),
+}
+
+/// The condition of an `if` statement.
+#[derive(Clone, Copy)]
+enum Condition {
+    Equal(u8, Operand),
+    NotEqual(u8, Operand),
+    Key(u8),
+    NotKey(u8),
+}
+
+impl Condition {
+    /// The opposite of this condition.
+    fn negate(self) -> Self {
+        match self {
+            Self::Equal(x, operand) => Self::NotEqual(x, operand),
+            Self::NotEqual(x, operand) => Self::Equal(x, operand),
+            Self::Key(x) => Self::NotKey(x),
+            Self::NotKey(x) => Self::Key(x),
+        }
+    }
+
+    /// The opcode that skips the next instruction unless this condition holds.
+    fn skip_unless(self) -> u16 {
+        let (opcode, x, low) = match self {
+            Self::Equal(x, Operand::Byte(nn)) => (0x4000, x, u16::from(nn)),
+            Self::NotEqual(x, Operand::Byte(nn)) => (0x3000, x, u16::from(nn)),
+            Self::Equal(x, Operand::Register(y)) => (0x9000, x, u16::from(y) << 4),
+            Self::NotEqual(x, Operand::Register(y)) => (0x5000, x, u16::from(y) << 4),
+            Self::Key(x) => (0xE000, x, 0xA1),
+            Self::NotKey(x) => (0xE000, x, 0x9E),
+        };
+        opcode | u16::from(x) << 8 | low
+    }
+}
+
+/// A reference to a label that is filled in once every label is known.
+struct Fixup<'a> {
+    index: usize,
+    label: &'a str,
+    line: usize,
+}
+
+struct Assembler<'a> {
+    tokens: Vec<Token<'a>>,
+    position: usize,
+    bytes: Vec<u8>,
+    labels: HashMap<&'a str, usize>,
+    constants: HashMap<&'a str, i64>,
+    aliases: HashMap<&'a str, u8>,
+    fixups: Vec<Fixup<'a>>,
+    blocks: Vec<Block>,
+    errors: Vec<Error>,
+}
+
+impl<'a> Assembler<'a> {
+    fn new(tokens: Vec<Token<'a>>) -> Self {
+        Self {
+            tokens,
+            position: 0,
+            bytes: Vec::new(),
+            labels: HashMap::new(),
+            constants: HashMap::new(),
+            aliases: HashMap::new(),
+            fixups: Vec::new(),
+            blocks: Vec::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    /// Assemble every statement, skipping the rest of the line after an error.
+    fn run(&mut self) {
+        let defines_main = self
+            .tokens
+            .windows(2)
+            .position(|pair| pair[0].text == ":" && pair[1].text == "main");
+        if defines_main.is_some_and(|position| position > 0) {
+            self.fixups.push(Fixup {
+                index: 0,
+                label: "main",
+                line: 1,
+            });
+            self.emit(0x1000);
+        }
+
+        while self.position < self.tokens.len() {
+            if let Err(e) = self.statement() {
+                while self
+                    .tokens
+                    .get(self.position)
+                    .is_some_and(|token| token.line <= e.line)
+                {
+                    self.position += 1;
+                }
+                self.errors.push(e);
+            }
+        }
+    }
+
+    /// Check that every block is closed and fill in the label references.
+    fn finish(mut self) -> Result<Vec<u8>, Vec<Error>> {
+        for block in std::mem::take(&mut self.blocks) {
+            let (line, keyword) = match block {
+                Block::Loop(_, line) => (line, "loop"),
+                Block::If(_, line) => (line, "begin"),
+            };
+            self.errors
+                .push(error(line, format!("This {keyword} is never closed.")));
+        }
+
+        for fixup in std::mem::take(&mut self.fixups) {
+            match self.labels.get(fixup.label) {
+                Some(&address) => self.patch_address(fixup.index, address),
+                None => {
+                    let message = format!("The label {} is never defined.", fixup.label);
+                    self.errors.push(error(fixup.line, message));
+                }
+            }
+        }
+
+        if self.errors.is_empty() {
+            Ok(self.bytes)
+        } else {
+            self.errors.sort_by_key(|e| e.line);
+            Err(self.errors)
+        }
+    }
+
+    fn statement(&mut self) -> Result<(), Error> {
+        let token = self.next()?;
+        match token.text {
+            ":" => {
+                let name = self.next()?;
+                self.define(name)?;
+                let address = self.address(name)?;
+                self.labels.insert(name.text, address);
+            }
+            ":const" => {
+                let name = self.next()?;
+                let value = self.next()?;
+                self.define(name)?;
+                let value = self.value(value).ok_or_else(|| {
+                    error(
+                        value.line,
+                        format!("Expected a number, found {}.", value.text),
+                    )
+                })?;
+                self.constants.insert(name.text, value);
+            }
+            ":alias" => {
+                let name = self.next()?;
+                let register = self.next()?;
+                self.define(name)?;
+                let register = self.register(register)?;
+                self.aliases.insert(name.text, register);
+            }
+            ":call" => {
+                let target = self.next()?;
+                self.emit_address(0x2000, target)?;
+            }
+            "clear" => self.emit(0x00E0),
+            "return" | ";" => self.emit(0x00EE),
+            "jump" => {
+                let target = self.next()?;
+                self.emit_address(0x1000, target)?;
+            }
+            "jump0" => {
+                let target = self.next()?;
+                self.emit_address(0xB000, target)?;
+            }
+            "loop" => {
+                let address = self.address(token)?;
+                self.blocks.push(Block::Loop(address, token.line));
+            }
+            "again" => match self.blocks.pop() {
+                Some(Block::Loop(address, _)) => self.emit(0x1000 | address as u16),
+                _ => return Err(error(token.line, "Found again without a loop.")),
+            },
+            "if" => self.conditional()?,
+            "else" => match self.blocks.pop() {
+                Some(Block::If(index, _)) => {
+                    let jump = self.bytes.len();
+                    self.emit(0x1000);
+                    self.patch_jump(index, token)?;
+                    self.blocks.push(Block::If(jump, token.line));
+                }
+                _ => return Err(error(token.line, "Found else without if ... begin.")),
+            },
+            "end" => match self.blocks.pop() {
+                Some(Block::If(index, _)) => self.patch_jump(index, token)?,
+                _ => return Err(error(token.line, "Found end without if ... begin.")),
+            },
+            "i" => self.index_statement()?,
+            "delay" | "buzzer" => {
+                self.expect(":=")?;
+                let x = self.next_register()?;
+                let low = if token.text == "delay" { 0x15 } else { 0x18 };
+                self.emit(0xF000 | u16::from(x) << 8 | low);
+            }
+            "bcd" | "save" | "load" => {
+                let x = self.next_register()?;
+                let low = match token.text {
+                    "bcd" => 0x33,
+                    "save" => 0x55,
+                    _ => 0x65,
+                };
+                self.emit(0xF000 | u16::from(x) << 8 | low);
+            }
+            "sprite" => {
+                let x = self.next_register()?;
+                let y = self.next_register()?;
+                let n = self.next()?;
+                let n = self.nibble(n)?;
+                self.emit(0xD000 | u16::from(x) << 8 | u16::from(y) << 4 | u16::from(n));
+            }
+            _ if self.is_register(token) => self.register_statement(token)?,
+            _ if self.value(token).is_some() => {
+                let byte = self.byte(token)?;
+                self.bytes.push(byte);
+            }
+            _ if is_label(token.text) => self.emit_address(0x2000, token)?,
+            _ => return Err(error(token.line, format!("Unexpected {}.", token.text))),
+        }
+        Ok(())
+    }
+
+    /// Assemble a statement that starts with the register `x`, such as `v0 += 1`.
+    fn register_statement(&mut self, x: Token<'a>) -> Result<(), Error> {
+        let x = u16::from(self.register(x)?) << 8;
+        let operator = self.next()?;
+        let rhs = self.next()?;
+
+        let opcode = match operator.text {
+            ":=" => match rhs.text {
+                "random" => {
+                    let mask = self.next()?;
+                    0xC000 | x | u16::from(self.byte(mask)?)
+                }
+                "delay" => 0xF007 | x,
+                "key" => 0xF00A | x,
+                _ if self.is_register(rhs) => 0x8000 | x | self.y(rhs)?,
+                _ => 0x6000 | x | u16::from(self.byte(rhs)?),
+            },
+            "+=" if self.is_register(rhs) => 0x8004 | x | self.y(rhs)?,
+            "+=" => 0x7000 | x | u16::from(self.byte(rhs)?),
+            "-=" if self.is_register(rhs) => 0x8005 | x | self.y(rhs)?,
+            "-=" => 0x7000 | x | u16::from(self.byte(rhs)?.wrapping_neg()),
+            "|=" => 0x8001 | x | self.y(rhs)?,
+            "&=" => 0x8002 | x | self.y(rhs)?,
+            "^=" => 0x8003 | x | self.y(rhs)?,
+            ">>=" => 0x8006 | x | self.y(rhs)?,
+            "=-" => 0x8007 | x | self.y(rhs)?,
+            "<<=" => 0x800E | x | self.y(rhs)?,
+            _ => {
+                let message = format!("Expected an assignment, found {}.", operator.text);
+                return Err(error(operator.line, message));
+            }
+        };
+        self.emit(opcode);
+        Ok(())
+    }
+
+    /// Assemble a statement that sets the index register, such as `i := sprite`.
+    fn index_statement(&mut self) -> Result<(), Error> {
+        let operator = self.next()?;
+        match operator.text {
+            ":=" => {
+                let rhs = self.next()?;
+                if rhs.text == "hex" {
+                    let x = self.next_register()?;
+                    self.emit(0xF029 | u16::from(x) << 8);
+                    Ok(())
+                } else {
+                    self.emit_address(0xA000, rhs)
+                }
+            }
+            "+=" => {
+                let x = self.next_register()?;
+                self.emit(0xF01E | u16::from(x) << 8);
+                Ok(())
+            }
+            _ => {
+                let message = format!("Expected := or +=, found {}.", operator.text);
+                Err(error(operator.line, message))
+            }
+        }
+    }
+
+    /// Assemble an `if` statement, after the `if` itself.
+    fn conditional(&mut self) -> Result<(), Error> {
+        let x = self.next_register()?;
+        let comparison = self.next()?;
+        let condition = match comparison.text {
+            "==" | "!=" => {
+                let rhs = self.next()?;
+                let operand = if self.is_register(rhs) {
+                    Operand::Register(self.register(rhs)?)
+                } else {
+                    Operand::Byte(self.byte(rhs)?)
+                };
+                if comparison.text == "==" {
+                    Condition::Equal(x, operand)
+                } else {
+                    Condition::NotEqual(x, operand)
+                }
+            }
+            "key" => Condition::Key(x),
+            "-key" => Condition::NotKey(x),
+            _ => {
+                let message = format!("Expected ==, !=, key or -key, found {}.", comparison.text);
+                return Err(error(comparison.line, message));
+            }
+        };
+
+        let keyword = self.next()?;
+        match keyword.text {
+            "then" => self.emit(condition.skip_unless()),
+            "begin" => {
+                // skip the jump past the block if the condition holds
+                self.emit(condition.negate().skip_unless());
+                self.blocks.push(Block::If(self.bytes.len(), keyword.line));
+                self.emit(0x1000);
+            }
+            _ => {
+                let message = format!("Expected then or begin, found {}.", keyword.text);
+                return Err(error(keyword.line, message));
+            }
+        }
+        Ok(())
+    }
+
+    /// Emit `opcode` with the address of `target` as its `NNN`, which may be a
+    /// number, a constant, or a label that is defined later.
+    fn emit_address(&mut self, opcode: u16, target: Token<'a>) -> Result<(), Error> {
+        if let Some(value) = self.value(target) {
+            let address = u16::try_from(value)
+                .ok()
+                .filter(|&address| address <= 0xFFF)
+                .ok_or_else(|| error(target.line, format!("{value} is not a valid address.")))?;
+            self.emit(opcode | address);
+        } else if is_label(target.text) {
+            self.fixups.push(Fixup {
+                index: self.bytes.len(),
+                label: target.text,
+                line: target.line,
+            });
+            self.emit(opcode);
+        } else {
+            let message = format!("Expected an address, found {}.", target.text);
+            return Err(error(target.line, message));
+        }
+        Ok(())
+    }
+
+    fn emit(&mut self, opcode: u16) {
+        self.bytes.extend_from_slice(&opcode.to_be_bytes());
+    }
+
+    /// Point the jump placeholder at `index` to the current address.
+    fn patch_jump(&mut self, index: usize, token: Token<'a>) -> Result<(), Error> {
+        let address = self.address(token)?;
+        self.patch_address(index, address);
+        Ok(())
+    }
+
+    /// Fill in the `NNN` of the opcode at `index` with `address`.
+    fn patch_address(&mut self, index: usize, address: usize) {
+        self.bytes[index] |= (address >> 8) as u8 & 0x0F;
+        self.bytes[index + 1] = address as u8;
+    }
+
+    /// The address the next byte is assembled at, which `token` refers to.
+    fn address(&self, token: Token<'a>) -> Result<usize, Error> {
+        let address = PROGRAM_START + self.bytes.len();
+        if address > 0xFFF {
+            return Err(error(token.line, "The program doesn't fit in memory."));
+        }
+        Ok(address)
+    }
+
+    /// Check that `name` can be defined as a new label, constant or alias.
+    fn define(&self, name: Token<'a>) -> Result<(), Error> {
+        if !is_label(name.text) || self.is_register(name) {
+            return Err(error(
+                name.line,
+                format!("{} is not a valid name.", name.text),
+            ));
+        }
+
+        let defined = self.labels.contains_key(name.text)
+            || self.constants.contains_key(name.text)
+            || self.aliases.contains_key(name.text);
+        if defined {
+            return Err(error(
+                name.line,
+                format!("{} is already defined.", name.text),
+            ));
+        }
+        Ok(())
+    }
+
+    fn next(&mut self) -> Result<Token<'a>, Error> {
+        let Some(&token) = self.tokens.get(self.position) else {
+            let line = self.tokens.last().map_or(1, |token| token.line);
+            return Err(error(line, "Unexpected end of file."));
+        };
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, text: &str) -> Result<(), Error> {
+        let token = self.next()?;
+        if token.text != text {
+            return Err(error(
+                token.line,
+                format!("Expected {text}, found {}.", token.text),
+            ));
+        }
+        Ok(())
+    }
+
+    fn next_register(&mut self) -> Result<u8, Error> {
+        let token = self.next()?;
+        self.register(token)
+    }
+
+    /// The register `token` as the `Y` of an `8XYN` opcode.
+    fn y(&self, token: Token<'a>) -> Result<u16, Error> {
+        Ok(u16::from(self.register(token)?) << 4)
+    }
+
+    fn is_register(&self, token: Token<'a>) -> bool {
+        self.register(token).is_ok()
+    }
+
+    /// Parse a register, such as `v3`, `VA` or an alias.
+    fn register(&self, token: Token<'a>) -> Result<u8, Error> {
+        if let Some(&register) = self.aliases.get(token.text) {
+            return Ok(register);
+        }
+
+        token
+            .text
+            .strip_prefix(['v', 'V'])
+            .filter(|digit| digit.len() == 1)
+            .and_then(|digit| u8::from_str_radix(digit, 16).ok())
+            .ok_or_else(|| {
+                error(
+                    token.line,
+                    format!("Expected a register, found {}.", token.text),
+                )
+            })
+    }
+
+    /// Parse a byte, allowing negative numbers down to `-128`.
+    fn byte(&self, token: Token<'a>) -> Result<u8, Error> {
+        match self.value(token) {
+            Some(value @ -128..=255) => Ok(value as u8),
+            Some(value) => Err(error(token.line, format!("{value} doesn't fit in a byte."))),
+            None => Err(error(
+                token.line,
+                format!("Expected a number, found {}.", token.text),
+            )),
+        }
+    }
+
+    fn nibble(&self, token: Token<'a>) -> Result<u8, Error> {
+        match self.value(token) {
+            Some(value @ 0..=15) => Ok(value as u8),
+            _ => Err(error(
+                token.line,
+                format!("Expected a number from 0 to 15, found {}.", token.text),
+            )),
+        }
+    }
+
+    /// The value of a number (decimal, `0x` hex or `0b` binary) or constant.
+    fn value(&self, token: Token<'a>) -> Option<i64> {
+        if let Some(&value) = self.constants.get(token.text) {
+            return Some(value);
+        }
+
+        let (negative, text) = match token.text.strip_prefix('-') {
+            Some(text) => (true, text),
+            None => (false, token.text),
+        };
+        let value = if let Some(hex) = text.strip_prefix("0x") {
+            i64::from_str_radix(hex, 16).ok()?
+        } else if let Some(binary) = text.strip_prefix("0b") {
+            i64::from_str_radix(binary, 2).ok()?
+        } else if text.starts_with(|c: char| c.is_ascii_digit()) {
+            text.parse().ok()?
+        } else {
+            return None;
+        };
+        Some(if negative { -value } else { value })
+    }
+}
+
+/// Returns `true` if `text` can be the name of a label.
+fn is_label(text: &str) -> bool {
+    !KEYWORDS.contains(&text)
+        && text.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && text
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn error(line: usize, message: impl Into<String>) -> Error {
+    Error {
+        line,
+        message: message.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Assemble `source`, which must be valid, into big-endian words.
+    fn words(source: &str) -> Vec<u16> {
+        let bytes = assemble(source).unwrap();
+        bytes
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+            .collect()
+    }
+
+    fn errors(source: &str) -> Vec<Error> {
+        assemble(source).unwrap_err()
+    }
+
+    #[test]
+    fn statements() {
+        let source = "
+            clear return ;
+            jump 0x234 jump0 0x300 :call 0x400
+            delay := v1 buzzer := v2
+            bcd v3 save v4 load v5
+            sprite v1 v2 5
+            i := 0x345 i := hex v6 i += v7
+        ";
+        assert_eq!(
+            words(source),
+            [
+                0x00E0, 0x00EE, 0x00EE, 0x1234, 0xB300, 0x2400, 0xF115, 0xF218, 0xF333, 0xF455,
+                0xF565, 0xD125, 0xA345, 0xF629, 0xF71E,
+            ]
+        );
+    }
+
+    #[test]
+    fn register_statements() {
+        let source = "
+            v0 := 0x12 v1 := v2 v3 := random 0x0F v4 := delay v5 := key
+            v6 += 3 v7 += v8 va -= vb vc -= 1
+            vd |= ve vd &= ve vd ^= ve vd >>= ve vd =- ve vd <<= ve
+        ";
+        assert_eq!(
+            words(source),
+            [
+                0x6012, 0x8120, 0xC30F, 0xF407, 0xF50A, 0x7603, 0x8784, 0x8AB5, 0x7CFF, 0x8DE1,
+                0x8DE2, 0x8DE3, 0x8DE6, 0x8DE7, 0x8DEE,
+            ]
+        );
+    }
+
+    #[test]
+    fn constants_aliases_and_bytes() {
+        let source = ":const seven 7 :alias x v3 x := seven 0xFF 0b1010 -1";
+        assert_eq!(assemble(source).unwrap(), [0x63, 0x07, 0xFF, 0x0A, 0xFF]);
+    }
+
+    #[test]
+    fn if_then_skips() {
+        let source = "
+            if v1 == 5 then if v1 != 5 then
+            if v1 == v2 then if v1 != v2 then
+            if v1 key then if v1 -key then
+        ";
+        assert_eq!(
+            words(source),
+            [0x4105, 0x3105, 0x9120, 0x5120, 0xE1A1, 0xE19E]
+        );
+    }
+
+    #[test]
+    fn if_begin_else_end() {
+        let source = "
+            if v0 == 1 begin
+                v1 := 1
+            else
+                v1 := 2
+            end
+        ";
+        // skip the jump to the else branch if v0 == 1, and jump past it after the if branch
+        assert_eq!(words(source), [0x3001, 0x1208, 0x6101, 0x120A, 0x6102]);
+    }
+
+    #[test]
+    fn loop_again() {
+        assert_eq!(words("loop v0 += 1 again"), [0x7001, 0x1200]);
+    }
+
+    #[test]
+    fn forward_labels() {
+        let source = "
+            jump done
+            helper
+            : done
+            clear
+            : helper
+        ";
+        assert_eq!(words(source), [0x1204, 0x2206, 0x00E0]);
+    }
+
+    #[test]
+    fn jumps_to_main() {
+        let source = ": block 0xF0 0x90 : main i := block";
+        assert_eq!(
+            assemble(source).unwrap(),
+            [0x12, 0x04, 0xF0, 0x90, 0xA2, 0x02]
+        );
+
+        // no jump is needed if the program starts with main
+        assert_eq!(words(": main clear"), [0x00E0]);
+    }
+
+    #[test]
+    fn undefined_label() {
+        assert_eq!(
+            errors("clear\njump nowhere"),
+            [error(2, "The label nowhere is never defined.")]
+        );
+    }
+
+    #[test]
+    fn unclosed_loop() {
+        assert_eq!(
+            errors("clear\nloop\nv0 += 1"),
+            [error(2, "This loop is never closed.")]
+        );
+    }
+
+    #[test]
+    fn byte_out_of_range() {
+        assert_eq!(
+            errors("clear\n\nv0 := 256"),
+            [error(3, "256 doesn't fit in a byte.")]
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::f64::consts::{PI, TAU};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::System;
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+pub use web::System;
+
+/// The frequency of the beep, in Hz, when none is configured.
+pub const DEFAULT_FREQUENCY: f32 = 440.0;
+
+/// The range of frequencies, in Hz, the beep can be set to.
+pub const FREQUENCY_RANGE: std::ops::RangeInclusive<f32> = 250.0..=1000.0;
+
+/// The volume of the beep when none is configured.
+pub const DEFAULT_VOLUME: f32 = 0.2;
+
+/// The shape of the wave played while the `Chip8` sound timer is active.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub enum Waveform {
+    Square,
+    Sine,
+    #[default]
+    Triangle,
+}
+
+impl Waveform {
+    /// Every available [`Waveform`], in the order they are displayed.
+    pub const ALL: [Self; 3] = [Self::Square, Self::Sine, Self::Triangle];
+
+    /// A stable index for this [`Waveform`], the inverse of [`Waveform::from_index`].
+    #[must_use]
+    pub fn to_index(self) -> u8 {
+        match self {
+            Self::Square => 0,
+            Self::Sine => 1,
+            Self::Triangle => 2,
+        }
+    }
+
+    /// The [`Waveform`] with the given index from [`Waveform::to_index`], if any.
+    #[must_use]
+    pub fn from_index(index: u8) -> Option<Self> {
+        match index {
+            0 => Some(Self::Square),
+            1 => Some(Self::Sine),
+            2 => Some(Self::Triangle),
+            _ => None,
+        }
+    }
+
+    /// The amplitude of the wave, between `-1.0` and `1.0`, at the given `phase`
+    /// of a single period, where `phase` is between `0.0` and `1.0`.
+    #[must_use]
+    pub fn sample(self, phase: f64) -> f64 {
+        match self {
+            Self::Square if phase < 0.5 => 1.0,
+            Self::Square => -1.0,
+            Self::Sine => (TAU * phase).sin(),
+            Self::Triangle => (TAU * phase).sin().asin() * 2.0 / PI,
+        }
+    }
+}
+
+impl std::fmt::Display for Waveform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Square => "Square",
+            Self::Sine => "Sine",
+            Self::Triangle => "Triangle",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The user-configurable audio settings.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// The frequency of the beep, in Hz.
+    pub frequency: f32,
+    pub waveform: Waveform,
+    /// The multiplier applied to the beep, between `0.0` and `1.0`.
+    pub volume: f32,
+    pub muted: bool,
+}
+
+impl Settings {
+    /// The multiplier applied to each sample, taking muting into account.
+    #[must_use]
+    pub fn gain(&self) -> f32 {
+        if self.muted {
+            0.0
+        } else {
+            self.volume.clamp(0.0, 1.0)
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            frequency: DEFAULT_FREQUENCY,
+            waveform: Waveform::default(),
+            volume: DEFAULT_VOLUME,
+            muted: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waveform_index_round_trip() {
+        for waveform in Waveform::ALL {
+            assert_eq!(Waveform::from_index(waveform.to_index()), Some(waveform));
+        }
+        assert_eq!(Waveform::from_index(3), None);
+    }
+}
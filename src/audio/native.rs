@@ -0,0 +1,183 @@
+use std::sync::{
+    atomic::{AtomicU32, AtomicU8, Ordering},
+    Arc,
+};
+
+use anyhow::{bail, Context};
+use cpal::{
+    traits::{DeviceTrait, HostTrait, StreamTrait},
+    Device, Sample, Stream, StreamConfig,
+};
+
+use super::{Settings, Waveform, FREQUENCY_RANGE};
+
+/// How long, in seconds, the tone takes to fade in and out. This avoids the
+/// audible click caused by starting or stopping a wave at a non-zero amplitude.
+const RAMP_DURATION: f64 = 0.005;
+
+/// Manages the audio on the current system, and plays a single
+/// frequency whenever the `Chip8` sound timer is above `0`.
+pub struct System {
+    stream: Stream,
+    settings: SharedSettings,
+}
+
+/// [`Settings`] stored atomically, so they can be changed while the stream is playing.
+#[derive(Clone)]
+struct SharedSettings {
+    /// The bit pattern of the frequency as an `f32`.
+    frequency: Arc<AtomicU32>,
+    /// The waveform, as its [`Waveform::to_index`].
+    waveform: Arc<AtomicU8>,
+    /// The bit pattern of the gain as an `f32`, which is `0.0` when muted.
+    gain: Arc<AtomicU32>,
+}
+
+impl SharedSettings {
+    fn new(settings: Settings) -> Self {
+        let shared = Self {
+            frequency: Arc::default(),
+            waveform: Arc::default(),
+            gain: Arc::default(),
+        };
+        shared.store(settings);
+        shared
+    }
+
+    fn store(&self, settings: Settings) {
+        let frequency = settings
+            .frequency
+            .clamp(*FREQUENCY_RANGE.start(), *FREQUENCY_RANGE.end());
+        self.frequency.store(frequency.to_bits(), Ordering::Relaxed);
+        self.waveform
+            .store(settings.waveform.to_index(), Ordering::Relaxed);
+        self.gain
+            .store(settings.gain().to_bits(), Ordering::Relaxed);
+    }
+
+    fn frequency(&self) -> f64 {
+        f64::from(f32::from_bits(self.frequency.load(Ordering::Relaxed)))
+    }
+
+    fn waveform(&self) -> Waveform {
+        Waveform::from_index(self.waveform.load(Ordering::Relaxed)).unwrap_or_default()
+    }
+
+    fn gain(&self) -> f64 {
+        f64::from(f32::from_bits(self.gain.load(Ordering::Relaxed)))
+    }
+}
+
+impl System {
+    /// Create a new [`System`] associated with the given sound timer, playing
+    /// the tone described by `settings`.
+    ///
+    /// Whenver the sound timer is above `0`, a frequency will play (assuming
+    /// `System::play` has been called beforehand).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the system has no audio output device, or if an
+    /// audio stream cannot be created for it.
+    pub fn new(timer: Arc<AtomicU8>, settings: Settings) -> anyhow::Result<Self> {
+        let host = cpal::default_host();
+        let device = host
+            .default_output_device()
+            .context("No audio output device is available.")?;
+
+        let settings = SharedSettings::new(settings);
+        Self::get_stream(&device, timer, settings.clone()).map(|stream| Self { stream, settings })
+    }
+
+    /// Change the tone played by this [`System`] without restarting the stream.
+    pub fn apply(&self, settings: Settings) {
+        self.settings.store(settings);
+    }
+
+    /// Create and retrieve a [`Stream`] depending on the sample format of the given [`Device`].
+    fn get_stream(
+        device: &Device,
+        timer: Arc<AtomicU8>,
+        settings: SharedSettings,
+    ) -> anyhow::Result<Stream> {
+        let config = device.default_output_config()?;
+        let format = config.sample_format();
+        let config = config.into();
+        match format {
+            cpal::SampleFormat::I8 => Self::create_stream::<i8>(device, &config, timer, settings),
+            cpal::SampleFormat::I16 => Self::create_stream::<i16>(device, &config, timer, settings),
+            cpal::SampleFormat::I32 => Self::create_stream::<i32>(device, &config, timer, settings),
+            cpal::SampleFormat::I64 => Self::create_stream::<i64>(device, &config, timer, settings),
+            cpal::SampleFormat::U8 => Self::create_stream::<u8>(device, &config, timer, settings),
+            cpal::SampleFormat::U16 => Self::create_stream::<u16>(device, &config, timer, settings),
+            cpal::SampleFormat::U32 => Self::create_stream::<u32>(device, &config, timer, settings),
+            cpal::SampleFormat::U64 => Self::create_stream::<u64>(device, &config, timer, settings),
+            cpal::SampleFormat::F32 => Self::create_stream::<f32>(device, &config, timer, settings),
+            cpal::SampleFormat::F64 => Self::create_stream::<f64>(device, &config, timer, settings),
+            // `SampleFormat` is non-exhaustive, so newer formats are reported instead
+            format => bail!("Unsupported audio sample format {format}."),
+        }
+    }
+
+    /// Create a new [`Stream`].
+    fn create_stream<T>(
+        device: &Device,
+        config: &StreamConfig,
+        timer: Arc<AtomicU8>,
+        settings: SharedSettings,
+    ) -> anyhow::Result<Stream>
+    where
+        T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f64>,
+    {
+        let sample_rate = f64::from(config.sample_rate.0);
+        let channels = usize::from(config.channels);
+        let ramp_step = 1.0 / (sample_rate * RAMP_DURATION);
+
+        let mut phase = 0f64;
+        let mut amplitude = 0f64;
+        let mut next_sample = move || {
+            let target = if timer.load(Ordering::SeqCst) > 0 {
+                1.0
+            } else {
+                0.0
+            };
+            if amplitude < target {
+                amplitude = (amplitude + ramp_step).min(target);
+            } else {
+                amplitude = (amplitude - ramp_step).max(target);
+            }
+
+            if amplitude == 0.0 {
+                phase = 0.0;
+                return 0.0;
+            }
+
+            phase = (phase + settings.frequency() / sample_rate) % 1.0;
+            settings.waveform().sample(phase) * amplitude * settings.gain()
+        };
+
+        let stream = device.build_output_stream(
+            config,
+            move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
+                for frame in data.chunks_mut(channels) {
+                    let value: T = next_sample().to_sample();
+                    for sample in frame.iter_mut() {
+                        *sample = value;
+                    }
+                }
+            },
+            |err| log::error!("An error occurred on the audio stream: {err}"),
+            None,
+        )?;
+        Ok(stream)
+    }
+
+    /// Play the audio stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the audio stream fails to play.
+    pub fn play(&self) -> anyhow::Result<()> {
+        self.stream.play().context("Failed to play audio stream.")
+    }
+}
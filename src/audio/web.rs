@@ -0,0 +1,139 @@
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+};
+
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AudioContext, AudioContextState, GainNode, OscillatorNode, OscillatorType};
+
+use super::{Settings, Waveform};
+use crate::web::js_error;
+
+/// The time constant, in seconds, of the gain fading in and out. This avoids the
+/// audible click caused by starting or stopping a wave at a non-zero amplitude.
+const RAMP_TIME_CONSTANT: f64 = 0.005;
+
+/// Manages the audio in the browser using the Web Audio API, and plays a
+/// single frequency whenever the `Chip8` sound timer is above `0`.
+///
+/// Browsers block audio until the user has interacted with the page, so the
+/// audio context starts suspended and is only resumed once [`System::play`]
+/// is called in response to a user gesture. The context is kept for the
+/// lifetime of the app, see [`System::set_timer`].
+pub struct System {
+    context: AudioContext,
+    oscillator: OscillatorNode,
+    gain: GainNode,
+    timer: Arc<AtomicU8>,
+    /// The gain applied while the sound timer is above `0`.
+    volume: Cell<f32>,
+    /// The gain the output is currently fading towards.
+    target: Cell<f32>,
+}
+
+impl System {
+    /// Create a new [`System`] associated with the given sound timer, playing
+    /// the tone described by `settings`.
+    ///
+    /// Unlike the native audio system, there is no audio callback polling the
+    /// sound timer, so [`System::update`] must be called every frame.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the browser doesn't support the Web Audio API, or if
+    /// any of the audio nodes cannot be created or connected.
+    pub fn new(timer: Arc<AtomicU8>, settings: Settings) -> anyhow::Result<Self> {
+        let context = AudioContext::new().map_err(js_error)?;
+        let oscillator = context.create_oscillator().map_err(js_error)?;
+        let gain = context.create_gain().map_err(js_error)?;
+        gain.gain().set_value(0.0);
+
+        oscillator
+            .connect_with_audio_node(&gain)
+            .map_err(js_error)?;
+        gain.connect_with_audio_node(&context.destination())
+            .map_err(js_error)?;
+        oscillator.start().map_err(js_error)?;
+
+        let system = Self {
+            context,
+            oscillator,
+            gain,
+            timer,
+            volume: Cell::new(0.0),
+            target: Cell::new(0.0),
+        };
+        system.apply(settings);
+        Ok(system)
+    }
+
+    /// Follow a new sound timer, keeping the audio context. A new context would
+    /// start suspended again, and wouldn't be resumed until the next user gesture.
+    pub fn set_timer(&mut self, timer: Arc<AtomicU8>) {
+        self.timer = timer;
+        self.update();
+    }
+
+    /// Change the tone played by this [`System`] without recreating the audio nodes.
+    pub fn apply(&self, settings: Settings) {
+        let oscillator_type = match settings.waveform {
+            Waveform::Square => OscillatorType::Square,
+            Waveform::Sine => OscillatorType::Sine,
+            Waveform::Triangle => OscillatorType::Triangle,
+        };
+        self.oscillator.set_type(oscillator_type);
+        self.oscillator.frequency().set_value(settings.frequency);
+        self.volume.set(settings.gain());
+        self.update();
+    }
+
+    /// Resume the audio context if it is suspended. This only has an effect when
+    /// called in response to a user gesture, such as a click or a key press.
+    ///
+    /// Resuming completes asynchronously, so if the browser doesn't allow it yet,
+    /// this is only logged, and the next user gesture will try again.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the audio context can't start resuming.
+    pub fn play(&self) -> anyhow::Result<()> {
+        if self.context.state() == AudioContextState::Suspended {
+            let resume = JsFuture::from(self.context.resume().map_err(js_error)?);
+            wasm_bindgen_futures::spawn_local(async move {
+                if let Err(e) = resume.await {
+                    log::warn!("Failed to resume audio: {}", js_error(e));
+                }
+            });
+        }
+        Ok(())
+    }
+
+    /// Fade the tone in or out depending on the current value of the sound timer.
+    pub fn update(&self) {
+        let target = if self.timer.load(Ordering::SeqCst) > 0 {
+            self.volume.get()
+        } else {
+            0.0
+        };
+
+        if target != self.target.get() {
+            self.target.set(target);
+            let _ = self.gain.gain().set_target_at_time(
+                target,
+                self.context.current_time(),
+                RAMP_TIME_CONSTANT,
+            );
+        }
+    }
+}
+
+impl Drop for System {
+    /// Browsers limit the amount of open audio contexts, so close it
+    /// rather than waiting for it to be garbage collected.
+    fn drop(&mut self) {
+        let _ = self.context.close();
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use clap::Parser;
+use egui::Color32;
+
+/// A Chip8 emulator and debugger.
+#[derive(Parser)]
+#[command(version, about)]
+pub struct Args {
+    /// The ROM to load on startup.
+    pub rom: Option<PathBuf>,
+
+    /// The amount of cycles executed per second.
+    #[arg(long, value_name = "CYCLES")]
+    pub speed: Option<u32>,
+
+    /// Enable the shift quirk in the interpreter.
+    #[arg(long)]
+    pub shift_quirk: bool,
+
+    /// Wait for the vertical blank interrupt before drawing a sprite.
+    #[arg(long)]
+    pub vblank_wait: bool,
+
+    /// The foreground color, as a hex color such as `#33FF66`.
+    #[arg(long, value_name = "HEX", value_parser = parse_color)]
+    pub fg: Option<Color32>,
+
+    /// The background color, as a hex color such as `#000000`.
+    #[arg(long, value_name = "HEX", value_parser = parse_color)]
+    pub bg: Option<Color32>,
+
+    /// Start with execution paused.
+    #[arg(long)]
+    pub paused: bool,
+}
+
+/// Parse a color written as six hex digits, with an optional leading `#`.
+fn parse_color(text: &str) -> Result<Color32, String> {
+    let hex = text.strip_prefix('#').unwrap_or(text);
+    if hex.len() != 6 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Err(format!(
+            "expected six hex digits, such as #33FF66, got {text}"
+        ));
+    }
+
+    let rgb = u32::from_str_radix(hex, 16).map_err(|e| format!("invalid hex color {text}: {e}"))?;
+    let [_, red, green, blue] = rgb.to_be_bytes();
+    Ok(Color32::from_rgb(red, green, blue))
+}
@@ -0,0 +1,149 @@
+use std::collections::BTreeSet;
+
+/// The address programs are loaded at, mirroring the `Chip8` memory layout.
+pub const PROGRAM_START: usize = 0x200;
+
+/// The total amount of addressable memory, mirroring the `Chip8` memory layout.
+pub const MEMORY_SIZE: usize = 4096;
+
+/// The size of the built-in font (16 sprites of 5 bytes) at the start of memory,
+/// mirroring the `Chip8` memory layout.
+pub const FONT_SIZE: usize = 16 * 5;
+
+/// Returns `true` if `opcode` at `address` is a jump to itself (`1NNN` with
+/// `NNN == address`), which programs commonly use to halt once they're finished.
+#[must_use]
+pub fn is_self_jump(address: usize, opcode: usize) -> bool {
+    opcode & 0xF000 == 0x1000 && opcode & 0x0FFF == address
+}
+
+/// Decode a two byte `opcode` into a human-readable description.
+///
+/// Words that don't decode to a valid instruction are most likely data
+/// (such as sprites), and are displayed as a raw word instead.
+#[must_use]
+pub fn disassemble(opcode: usize) -> String {
+    describe(opcode).unwrap_or_else(|| data(opcode))
+}
+
+/// Returns `true` if `opcode` decodes to an instruction, rather than data like
+/// the `0x0000` of unused memory.
+#[must_use]
+pub fn is_instruction(opcode: usize) -> bool {
+    describe(opcode).is_some()
+}
+
+/// Describe the instruction `opcode` decodes to, or `None` if it isn't one.
+fn describe(opcode: usize) -> Option<String> {
+    let x = (opcode & 0x0F00) >> 8;
+    let y = (opcode & 0x00F0) >> 4;
+    let n = opcode & 0x000F;
+    let nn = opcode & 0x00FF;
+    let nnn = opcode & 0x0FFF;
+
+    let description = match (opcode & 0xF000) >> 12 {
+        0x0 if opcode == 0x00E0 => "Clear the screen".to_owned(),
+        0x0 if opcode == 0x00EE => "Return from subroutine".to_owned(),
+        0x1 => format!("Jump to {nnn:#05X}"),
+        0x2 => format!("Call subroutine at {nnn:#05X}"),
+        0x3 => format!("Skip if V{x:X} == {nn:#04X}"),
+        0x4 => format!("Skip if V{x:X} != {nn:#04X}"),
+        0x5 if n == 0 => format!("Skip if V{x:X} == V{y:X}"),
+        0x6 => format!("V{x:X} = {nn:#04X}"),
+        0x7 => format!("V{x:X} += {nn:#04X}"),
+        0x8 => match n {
+            0x0 => format!("V{x:X} = V{y:X}"),
+            0x1 => format!("V{x:X} |= V{y:X}"),
+            0x2 => format!("V{x:X} &= V{y:X}"),
+            0x3 => format!("V{x:X} ^= V{y:X}"),
+            0x4 => format!("V{x:X} += V{y:X}, VF = carry"),
+            0x5 => format!("V{x:X} -= V{y:X}, VF = not borrow"),
+            0x6 => format!("V{x:X} = V{y:X} >> 1, VF = shifted bit"),
+            0x7 => format!("V{x:X} = V{y:X} - V{x:X}, VF = not borrow"),
+            0xE => format!("V{x:X} = V{y:X} << 1, VF = shifted bit"),
+            _ => return None,
+        },
+        0x9 if n == 0 => format!("Skip if V{x:X} != V{y:X}"),
+        0xA => format!("I = {nnn:#05X}"),
+        0xB => format!("Jump to V0 + {nnn:#05X}"),
+        0xC => format!("V{x:X} = random & {nn:#04X}"),
+        0xD => format!("Draw {n}-byte sprite at (V{x:X}, V{y:X})"),
+        0xE if nn == 0x9E => format!("Skip if key V{x:X} is pressed"),
+        0xE if nn == 0xA1 => format!("Skip if key V{x:X} is not pressed"),
+        0xF => match nn {
+            0x07 => format!("V{x:X} = delay timer"),
+            0x0A => format!("Wait for a key press, store it in V{x:X}"),
+            0x15 => format!("Delay timer = V{x:X}"),
+            0x18 => format!("Sound timer = V{x:X}"),
+            0x1E => format!("I += V{x:X}"),
+            0x29 => format!("I = font sprite for V{x:X}"),
+            0x33 => format!("Store BCD of V{x:X} at I"),
+            0x55 => format!("Store V0..=V{x:X} at I"),
+            0x65 => format!("Load V0..=V{x:X} from I"),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(description)
+}
+
+/// The synthetic label of a jump or call target at `address`, such as `L_0x2A6`.
+#[must_use]
+pub fn label(address: usize) -> String {
+    format!("L_{address:#05X}")
+}
+
+/// Collect the targets of every jump (`1NNN`, `BNNN`) and call (`2NNN`) in `opcodes`.
+///
+/// For `BNNN` the target depends on `V0`, so only the base address is collected.
+#[must_use]
+pub fn collect_labels(opcodes: impl IntoIterator<Item = usize>) -> BTreeSet<usize> {
+    opcodes
+        .into_iter()
+        .filter(|opcode| matches!(opcode >> 12, 0x1 | 0x2 | 0xB))
+        .map(|opcode| opcode & 0x0FFF)
+        .collect()
+}
+
+/// Decode a two byte `opcode` like [`disassemble`], but refer to jump and call
+/// targets by their [`label`] if they're in `labels`.
+#[must_use]
+pub fn disassemble_labeled(opcode: usize, labels: &BTreeSet<usize>) -> String {
+    let nnn = opcode & 0x0FFF;
+    if !labels.contains(&nnn) {
+        return disassemble(opcode);
+    }
+
+    match opcode >> 12 {
+        0x1 => format!("Jump to {}", label(nnn)),
+        0x2 => format!("Call subroutine at {}", label(nnn)),
+        0xB => format!("Jump to V0 + {}", label(nnn)),
+        _ => disassemble(opcode),
+    }
+}
+
+/// Display a word that isn't a valid instruction.
+fn data(opcode: usize) -> String {
+    format!("Data {opcode:#06X}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instructions() {
+        let cases = [
+            (0x0000, false),
+            (0x00E0, true),
+            (0x00EE, true),
+            (0x8008, false),
+            (0x800E, true),
+            (0xF0FF, false),
+            (0xF065, true),
+        ];
+        for (opcode, expected) in cases {
+            assert_eq!(is_instruction(opcode), expected, "{opcode:#06X}");
+        }
+    }
+}
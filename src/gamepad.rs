@@ -0,0 +1,99 @@
+use gilrs::{Button, EventType, Gilrs};
+use serde::{Deserialize, Serialize};
+
+/// The default mapping from gamepad buttons to Chip8 key codes. The d-pad
+/// matches the WASD keys of the default keyboard mapping.
+static DEFAULT_BUTTON_MAP: [(Button, u8); 8] = [
+    (Button::DPadUp, 0x5),
+    (Button::DPadLeft, 0x7),
+    (Button::DPadDown, 0x8),
+    (Button::DPadRight, 0x9),
+    (Button::South, 0x6),
+    (Button::East, 0x4),
+    (Button::West, 0xA),
+    (Button::North, 0xB),
+];
+
+/// Gamepad button bindings to the 16 Chip8 keys, indexed by key code.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct ButtonBindings([Option<Button>; 16]);
+
+impl Default for ButtonBindings {
+    fn default() -> Self {
+        let mut buttons = [None; 16];
+        for (button, key_code) in DEFAULT_BUTTON_MAP {
+            buttons[usize::from(key_code)] = Some(button);
+        }
+        Self(buttons)
+    }
+}
+
+impl ButtonBindings {
+    /// The button bound to the given Chip8 key code, if any.
+    #[must_use]
+    pub fn get(&self, key_code: u8) -> Option<Button> {
+        self.0[usize::from(key_code)]
+    }
+
+    /// Bind `button` to the given Chip8 key code.
+    pub fn set(&mut self, key_code: u8, button: Button) {
+        self.0[usize::from(key_code)] = Some(button);
+    }
+
+    /// The pressed state of each Chip8 key on the connected gamepads, indexed by key code.
+    #[must_use]
+    pub fn pressed_keys(&self, gamepads: &Gamepads) -> [bool; 16] {
+        self.0
+            .map(|button| button.is_some_and(|button| gamepads.is_pressed(button)))
+    }
+}
+
+/// Polls the connected gamepads. Gamepads can be connected and
+/// disconnected at any time while the app is running.
+pub struct Gamepads {
+    /// `None` if gamepads aren't supported on the current system.
+    gilrs: Option<Gilrs>,
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        let gilrs = Gilrs::new()
+            .map_err(|e| log::error!("Failed to initialize gamepad support: {e}"))
+            .ok();
+        Self { gilrs }
+    }
+}
+
+impl Gamepads {
+    /// Handle all pending gamepad events. This should be called once per frame.
+    ///
+    /// Returns the last button that was pressed since this was last called, if any.
+    pub fn poll(&mut self) -> Option<Button> {
+        let gilrs = self.gilrs.as_mut()?;
+
+        let mut pressed = None;
+        while let Some(event) = gilrs.next_event() {
+            match event.event {
+                EventType::ButtonPressed(button, _) => pressed = Some(button),
+                EventType::Connected => {
+                    log::info!("Gamepad connected: {}.", gilrs.gamepad(event.id).name());
+                }
+                EventType::Disconnected => {
+                    log::info!("Gamepad disconnected: {}.", gilrs.gamepad(event.id).name());
+                }
+                _ => {}
+            }
+        }
+        pressed
+    }
+
+    /// Returns `true` if `button` is held down on any connected gamepad.
+    #[must_use]
+    pub fn is_pressed(&self, button: Button) -> bool {
+        self.gilrs.as_ref().is_some_and(|gilrs| {
+            gilrs
+                .gamepads()
+                .any(|(_, gamepad)| gamepad.is_pressed(button))
+        })
+    }
+}
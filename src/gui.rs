@@ -1,26 +1,39 @@
 use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write as _,
     future::Future,
     path::PathBuf,
     sync::mpsc::{self, Receiver, Sender},
 };
 
 use chip8::{graphics::Rgb, Chip8};
-use eframe::{
-    egui::{self, Context, Key, Ui},
-    epaint::RectShape,
-};
-use egui::{Color32, Pos2, Rect, Rounding, Stroke};
+use eframe::egui::{self, Context, Key, KeyboardShortcut, Modifiers, Ui};
+use egui::{Color32, ColorImage, Pos2, Rect, TextureHandle, TextureOptions};
 
+#[cfg(not(target_arch = "wasm32"))]
+use gilrs::Button;
 use rfd::FileHandle;
 
+use crate::app::InvalidOpcodePolicy;
+use crate::assembler;
+use crate::audio::{self, Waveform};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::cli::Args;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::gamepad::{ButtonBindings, Gamepads};
+use crate::recorder::RecordingOptions;
+use crate::roms::BUILTIN_ROMS;
+
 use serde::{Deserialize, Serialize};
 
 use self::windows::{
-    InstructionsWindow, KeyWindow, ResgistersWindow, ScreenWindow, StackWindow, TimersWindow,
+    BreakpointsWindow, DisassemblyWindow, InstructionsWindow, KeyWindow, MemoryWindow,
+    RegistersWindow, ScreenWindow, StackWindow, TimersWindow,
 };
 
-/// Key mapping from a standard english keyboard to Chip8 key codes.
-static KEY_MAP: [(Key, u8); 16] = [
+/// The default key mapping from a standard english keyboard to Chip8 key codes,
+/// listed in the order of the Chip8 keypad layout.
+static DEFAULT_KEY_MAP: [(Key, u8); 16] = [
     (Key::Num1, 0x1),
     (Key::Num2, 0x2),
     (Key::Num3, 0x3),
@@ -39,14 +52,198 @@ static KEY_MAP: [(Key, u8); 16] = [
     (Key::V, 0xF),
 ];
 
+/// The shortcut that opens a ROM with a file dialog.
+const OPEN_ROM_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::O);
+
+/// The shortcut that resets the current ROM.
+const RESET_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::R);
+
+/// The key that toggles pause.
+const PAUSE_KEY: Key = Key::Space;
+
+/// The key that steps the `Chip8`, like the "step" button.
+const STEP_KEY: Key = Key::N;
+
+/// The key that rewinds the `Chip8` while held down.
+const REWIND_KEY: Key = Key::Backspace;
+
+/// The key that toggles fullscreen.
+const FULLSCREEN_KEY: Key = Key::F11;
+
+/// The shortcut that resets the zoom of the screen view, fitting the display to the window.
+/// This can't be `COMMAND+0`, as egui uses it to reset the zoom of the whole interface.
+const RESET_ZOOM_SHORTCUT: KeyboardShortcut = KeyboardShortcut::new(
+    Modifiers {
+        alt: true,
+        ctrl: false,
+        shift: false,
+        mac_cmd: false,
+        command: true,
+    },
+    Key::Num0,
+);
+
+/// The largest zoom of the screen view, in physical pixels per `Chip8` pixel.
+const MAX_ZOOM: u32 = 64;
+
+/// How much Ctrl+scroll input (as the logarithm of the egui zoom factor)
+/// changes the zoom of the screen view by one step.
+const ZOOM_INPUT_STEP: f32 = 0.1;
+
+/// The keys used to access the quick save state slots, in slot order.
+/// Pressing one saves to its slot, and holding shift loads from it instead.
+static SLOT_KEYS: [Key; 9] = [
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+];
+
+/// How much of a pixel's brightness remains after each 60Hz tick once it
+/// has been turned off, when the phosphor fade effect is enabled.
+const FADE_DECAY: f32 = 0.6;
+
+/// How long a pressed key stays visible in the key overlay after it's released, in seconds.
+const KEY_FLASH_TIME: f32 = 0.5;
+
+/// The default darkness of the scanline overlay, from `0.0` to `1.0`.
+const DEFAULT_SCANLINE_INTENSITY: f32 = 0.4;
+
+/// The default darkness of the pixel grid overlay, from `0.0` to `1.0`.
+const DEFAULT_PIXEL_GRID_INTENSITY: f32 = 0.25;
+
+/// The default amount of times per second a turbo key is pressed while held.
+const DEFAULT_TURBO_RATE: u32 = 10;
+
+/// Screens narrower than this, in points, show the touch keypad by default.
+const SMALL_SCREEN_WIDTH: f32 = 600.0;
+
+/// The state of the `App` that the `Gui` displays each frame.
+pub struct AppState<'a> {
+    pub chip8: &'a Chip8,
+    pub paused: bool,
+    pub breakpoints: &'a BTreeSet<usize>,
+    pub recent_roms: &'a [RecentRom],
+    /// The amount of instructions executed since the ROM was loaded.
+    pub instruction_count: u64,
+    /// The size of the loaded ROM, in bytes.
+    pub rom_size: usize,
+    /// The CRC32 hash of the loaded ROM.
+    pub rom_hash: u32,
+    /// `false` if the app is running without sound because audio is unavailable.
+    pub audio_available: bool,
+    /// `true` if the program has halted by jumping to itself.
+    pub halted: bool,
+    /// `true` if the rewind buffer has a snapshot to step back to.
+    pub can_step_back: bool,
+    pub draw_stats: DrawStats,
+    pub performance: Performance,
+    /// The errors of the last Octo source that failed to assemble.
+    pub assembly_errors: &'a [assembler::Error],
+    /// `true` while every executed instruction is traced to a file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub tracing: bool,
+    /// `true` while the pressed keys are being recorded.
+    pub input_recording: bool,
+    /// `true` while playing back an input recording of a ROM that draws random
+    /// numbers, which aren't reproduced by the playback.
+    pub replay_uses_random: bool,
+}
+
+/// The sprite draws (`Dxyn`) executed by the `Chip8` during a frame.
+#[derive(Clone, Copy, Default)]
+pub struct DrawStats {
+    pub draws: u32,
+
+    /// The draws that turned off a pixel, setting the collision flag `VF`.
+    pub collisions: u32,
+}
+
+/// The measured speed of the `App`, shown by the performance overlay.
+#[derive(Clone, Copy, Default, PartialEq)]
+pub struct Performance {
+    /// The amount of frames drawn per second.
+    pub fps: f32,
+
+    /// The amount of `Chip8` cycles executed per second.
+    pub cycles_per_second: f32,
+}
+
+/// A ROM that was recently opened, shown in the "Recent" menu.
+#[derive(Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RecentRom {
+    /// The file name of the ROM.
+    pub name: String,
+
+    /// The path the ROM was loaded from.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub path: PathBuf,
+
+    /// The ROM data itself, since file paths aren't available on the web.
+    #[cfg(target_arch = "wasm32")]
+    pub data: Vec<u8>,
+}
+
+impl RecentRom {
+    /// Create a new [`RecentRom`] from a file picked by the user, and its data.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn new(file: &FileHandle, _data: &[u8]) -> Self {
+        Self {
+            name: file.file_name(),
+            path: file.path().to_path_buf(),
+        }
+    }
+
+    /// Create a new [`RecentRom`] from a file picked by the user, and its data.
+    #[cfg(target_arch = "wasm32")]
+    fn new(file: &FileHandle, data: &[u8]) -> Self {
+        Self {
+            name: file.file_name(),
+            data: data.to_vec(),
+        }
+    }
+
+    /// Read the ROM data, returning `None` if it can no longer be read.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn read(&self) -> Option<Vec<u8>> {
+        std::fs::read(&self.path)
+            .map_err(|e| log::error!("Failed to read ROM from {}: {e}", self.path.display()))
+            .ok()
+    }
+
+    /// Read the ROM data, returning `None` if it can no longer be read.
+    #[cfg(target_arch = "wasm32")]
+    fn read(&self) -> Option<Vec<u8>> {
+        Some(self.data.clone())
+    }
+}
+
 /// A message sent from the GUI to the backend.
 pub enum Chip8Message {
     /// Load the given ROM into the `Chip8`.
     LoadRom(Vec<u8>),
 
-    /// Reset the currently loaded `Chip8` ROM.
+    /// Assemble the given Octo source, then load the result into the `Chip8`.
+    LoadSource(String),
+
+    /// Dismiss the errors of the last Octo source that failed to assemble.
+    ClearAssemblyErrors,
+
+    /// Move the given ROM to the top of the recently opened ROMs.
+    AddRecentRom(RecentRom),
+
+    /// Reset the currently loaded `Chip8` ROM, keeping the quirks and colors (warm reset).
     ResetROM,
 
+    /// Replace the `Chip8` with a freshly powered on one, restoring the default
+    /// quirks and colors, then load the current ROM again (cold reset).
+    ColdReset,
+
     /// Set the foreground color of the `Chip8` graphics.
     SetForegroundColor(Color32),
 
@@ -54,9 +251,30 @@ pub enum Chip8Message {
     SetBackgroundColor(Color32),
 
     /// Set the amount of steps the `Chip8` interpreter should
-    /// advance on each frame.
+    /// advance on each frame, assuming a 60Hz display.
+    ///
+    /// This is converted to cycles per second, see [`Chip8Message::SetCyclesPerSecond`].
     SetStepRate(u32),
 
+    /// Set the amount of instructions the `Chip8` interpreter should
+    /// execute per second, independent of the display refresh rate.
+    SetCyclesPerSecond(u32),
+
+    /// Sets what to do when the program counter reaches an invalid opcode.
+    SetInvalidOpcodePolicy(InvalidOpcodePolicy),
+
+    /// Set the frequency of the beep played while the sound timer is active, in Hz.
+    SetBeepFrequency(f32),
+
+    /// Set the shape of the wave played while the sound timer is active.
+    SetWaveform(Waveform),
+
+    /// Set the volume of the beep, between `0.0` and `1.0`.
+    SetVolume(f32),
+
+    /// Mute/unmute the beep without stopping the audio stream.
+    SetMuted(bool),
+
     /// Enable/disable the shift quirk in the Chip8 instance
     SetShiftQuirk(bool),
 
@@ -77,10 +295,106 @@ pub enum Chip8Message {
     /// Load the `Chip8` state and any `App` state.
     LoadState(PathBuf),
 
+    /// Save the `Chip8` state to the given quick save slot of the current ROM.
+    SaveStateSlot(u8),
+
+    /// Load the `Chip8` state from the given quick save slot of the current ROM.
+    LoadStateSlot(u8),
+
     /// This indicates that the "step" button was clicked,
     /// meaning the user would like to execute one step of the interpreter.
     /// This should still step the interpreter even if the execution is paused.
     Step,
+
+    /// Execute the given amount of steps of the interpreter, even if the execution
+    /// is paused. This stops early if a breakpoint is reached.
+    StepN(u32),
+
+    /// Step the interpreter, running any subroutine called by the current
+    /// instruction until it returns.
+    StepOver,
+
+    /// Run the interpreter until the current subroutine returns.
+    StepOut,
+
+    /// Run the interpreter until the program counter reaches the given address,
+    /// then pause it.
+    RunToAddress(usize),
+
+    /// Save a PNG screenshot of the current display to the given path.
+    SaveScreenshot(PathBuf),
+
+    /// Save the history of executed instructions as CSV to the given path.
+    ExportInstructionLog(PathBuf),
+
+    /// Start writing every executed instruction, with the registers before it
+    /// runs, to the file at the given path.
+    #[cfg(not(target_arch = "wasm32"))]
+    StartTrace(PathBuf),
+
+    /// Stop the running trace.
+    #[cfg(not(target_arch = "wasm32"))]
+    StopTrace,
+
+    /// Copy the current state of the interpreter to the clipboard as pretty JSON.
+    CopyStateJson,
+
+    /// Copy the display to the clipboard as text art, drawing pixels that are
+    /// on and off with the given characters.
+    CopyScreenAscii { on: char, off: char },
+
+    /// Start capturing each frame of the display for a GIF recording.
+    StartRecording(RecordingOptions),
+
+    /// Stop capturing frames for the current GIF recording.
+    StopRecording,
+
+    /// Encode the last stopped GIF recording and save it to the given path.
+    SaveRecording(PathBuf),
+
+    /// Restart the current ROM and start recording the pressed keys.
+    StartInputRecording,
+
+    /// Stop recording the pressed keys.
+    StopInputRecording,
+
+    /// Save the last stopped input recording as JSON to the given path.
+    SaveInputRecording(PathBuf),
+
+    /// Restart the current ROM and play back the given JSON input recording.
+    PlayInputRecording(Vec<u8>),
+
+    /// Pause execution whenever the program counter reaches the given address.
+    AddBreakpoint(usize),
+
+    /// Remove the breakpoint at the given address.
+    RemoveBreakpoint(usize),
+
+    /// Set the value of the `V` register at `index`.
+    SetRegister { index: u8, value: u8 },
+
+    /// Set the index register `I`. Values past the end of memory are clamped.
+    SetIndexRegister(usize),
+
+    /// Write `value` to the memory at `addr`.
+    WriteMemory { addr: usize, value: u8 },
+
+    /// Restore the previous frame from the rewind buffer. This is sent
+    /// every frame while the rewind control is held down.
+    Rewind,
+
+    /// Restore the snapshot taken before the last step while paused, undoing it.
+    StepBack,
+
+    /// Set the maximum amount of frames kept in the rewind buffer.
+    SetRewindCapacity(usize),
+
+    /// Set whether save states made with a different ROM are refused instead of
+    /// loaded with a warning.
+    SetRejectMismatchedStates(bool),
+
+    /// Set whether execution pauses whenever a ROM is loaded or reset.
+    SetStartPaused(bool),
 }
 
 /// The current view in the `Gui`.
@@ -100,8 +414,20 @@ enum CurrentView {
 pub struct Gui {
     menu_panel: MenuPanel,
     config_window: ConfigWindow,
+    key_bindings_window: KeyBindingsWindow,
+    touch_keypad: TouchKeypad,
+    performance_overlay: PerformanceOverlay,
+    rom_info_window: RomInfoWindow,
     debug_view: DebugView,
     current_view: CurrentView,
+    screen_view: ScreenView,
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    gamepads: Gamepads,
+    /// The size of the window before it entered fullscreen, restored when leaving it.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    windowed_size: Option<egui::Vec2>,
     #[serde(skip, default = "mpsc::channel")]
     pub message_channel: (Sender<Chip8Message>, Receiver<Chip8Message>),
 }
@@ -119,26 +445,64 @@ impl Gui {
         Self {
             menu_panel: MenuPanel::default(),
             config_window: ConfigWindow::default(),
+            key_bindings_window: KeyBindingsWindow::default(),
+            touch_keypad: TouchKeypad::default(),
+            performance_overlay: PerformanceOverlay::default(),
+            rom_info_window: RomInfoWindow::default(),
             debug_view: DebugView::default(),
             current_view: CurrentView::default(),
+            screen_view: ScreenView::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            gamepads: Gamepads::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            windowed_size: None,
             message_channel: mpsc::channel(),
         }
     }
 
+    /// Show the settings given on the command line in the configuration window.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn apply_args(&mut self, args: &Args) {
+        self.config_window.apply_args(args);
+    }
+
     /// Renders the next frame, which includes any UI updates as well
     /// as the `Chip8` graphics state.
-    pub fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame, chip8: &Chip8) {
+    pub fn update(&mut self, ctx: &Context, frame: &mut eframe::Frame, state: &AppState<'_>) {
+        let AppState {
+            chip8,
+            paused,
+            recent_roms,
+            ..
+        } = *state;
+
+        self.menu_panel.paused = paused;
+        self.menu_panel.halted = state.halted;
+        self.menu_panel.can_step_back = state.can_step_back;
+        self.menu_panel.input_recording = state.input_recording;
+        self.menu_panel.replay_uses_random = state.replay_uses_random;
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.menu_panel.tracing = state.tracing;
+        }
+        self.debug_view.paused = paused;
+
         let menu_response = self.menu_panel.update(
             ctx,
             frame,
             &self.current_view,
+            recent_roms,
             self.message_channel.0.clone(),
         );
         if let MenuPanelResponse::ToggleConfigWindow = menu_response {
             self.config_window.toggle_visibility();
         }
 
-        if let MenuPanelResponse::ToggleResgistersWindow = menu_response {
+        if let MenuPanelResponse::ToggleKeyBindingsWindow = menu_response {
+            self.key_bindings_window.toggle_visibility();
+        }
+
+        if let MenuPanelResponse::ToggleRegistersWindow = menu_response {
             self.debug_view.registers_window.toggle_visibility();
         }
 
@@ -162,47 +526,324 @@ impl Gui {
             self.debug_view.instructions_window.toggle_visibility();
         }
 
-        if let MenuPanelResponse::Reset = menu_response {
-            // send the color message to the chip8 backend so that
-            // it restores the color settings for this session
-            self.config_window
-                .push_color_messages(&mut self.message_channel.0);
+        if let MenuPanelResponse::ToggleBreakpointsWindow = menu_response {
+            self.debug_view.breakpoints_window.toggle_visibility();
+        }
+
+        if let MenuPanelResponse::ToggleDisassemblyWindow = menu_response {
+            self.debug_view.disassembly_window.toggle_visibility();
         }
+
+        if let MenuPanelResponse::ToggleMemoryWindow = menu_response {
+            self.debug_view.memory_window.toggle_visibility();
+        }
+
+        if let MenuPanelResponse::ColdReset = menu_response {
+            self.config_window.reset_chip8_settings();
+        }
+
         if let MenuPanelResponse::ToggleView = menu_response {
             self.current_view = match self.current_view {
                 CurrentView::Screen => CurrentView::Debug,
                 CurrentView::Debug => CurrentView::Screen,
             }
         }
-        if let MenuPanelResponse::TogglePause = menu_response {
-            self.menu_panel.toggle_pause();
-            self.debug_view.toggle_pause();
+        if let MenuPanelResponse::ToggleRecording = menu_response {
+            self.toggle_recording();
+        }
+        if let MenuPanelResponse::ToggleInputRecording = menu_response {
+            self.toggle_input_recording();
+        }
+
+        if let MenuPanelResponse::ToggleTouchKeypad = menu_response {
+            self.touch_keypad.toggle_visibility(ctx);
+        }
+
+        if let MenuPanelResponse::TogglePerformanceOverlay = menu_response {
+            self.performance_overlay.toggle_visibility();
+        }
+
+        if let MenuPanelResponse::ToggleRomInfoWindow = menu_response {
+            self.rom_info_window.toggle_visibility();
+        }
+
+        if let MenuPanelResponse::ToggleFullscreen = menu_response {
+            self.toggle_fullscreen(ctx);
+        }
+
+        if let MenuPanelResponse::ZoomIn = menu_response {
+            self.screen_view.zoom_by(1);
+        }
+
+        if let MenuPanelResponse::ZoomOut = menu_response {
+            self.screen_view.zoom_by(-1);
+        }
+
+        if let MenuPanelResponse::ResetZoom = menu_response {
+            self.screen_view.reset_zoom();
         }
 
+        // the keypad panel has to be drawn before the central panel of the current view
+        let keypad_keys = self.touch_keypad.update(ctx);
+
         match self.current_view {
-            CurrentView::Screen => ScreenView::update(ctx, chip8),
-            CurrentView::Debug => self.debug_view.update(ctx, chip8),
+            CurrentView::Screen => {
+                self.screen_view
+                    .update(ctx, chip8, &self.config_window.screen_options());
+            }
+            CurrentView::Debug => {
+                self.debug_view
+                    .update(ctx, state, &mut self.message_channel.0);
+            }
+        }
+
+        self.config_window
+            .update(ctx, state.audio_available, &mut self.message_channel.0);
+        self.performance_overlay.update(ctx, state.performance);
+        self.rom_info_window
+            .view(ctx, state.rom_size, state.rom_hash);
+        Self::update_assembly_errors(ctx, state.assembly_errors, &mut self.message_channel.0);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let pressed_button = self.gamepads.poll();
+
+        // keys pressed while rebinding shouldn't trigger anything else
+        let capturing = self.key_bindings_window.is_capturing();
+        self.key_bindings_window.update(
+            ctx,
+            #[cfg(not(target_arch = "wasm32"))]
+            pressed_button,
+        );
+        if capturing {
+            return;
         }
 
-        self.config_window.update(ctx, &mut self.message_channel.0);
+        self.update_fullscreen(ctx);
+        self.update_shortcuts(ctx);
+        let bindings = &self.key_bindings_window.bindings;
+        Self::update_rewind(ctx, bindings, &mut self.message_channel.0);
+        Self::update_state_slots(ctx, bindings, &mut self.message_channel.0);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let gamepad_keys = self
+            .key_bindings_window
+            .buttons
+            .pressed_keys(&self.gamepads);
+        #[cfg(target_arch = "wasm32")]
+        let gamepad_keys = [false; 16];
+
+        let held_keys = std::array::from_fn(|code| gamepad_keys[code] || keypad_keys[code]);
+
+        // turbo keys are held steady while the Chip8 waits for a key press (Fx0A),
+        // so that the pulses don't register as several separate presses
+        let released_keys = if chip8.bus.input.waiting() {
+            0
+        } else {
+            self.config_window.turbo_released_keys(ctx)
+        };
 
-        Self::update_key_state(ctx, &mut self.message_channel.0);
+        let bindings = &self.key_bindings_window.bindings;
+        let messages = &mut self.message_channel.0;
+        Self::update_key_state(ctx, bindings, held_keys, released_keys, messages);
     }
 
-    /// Handles key events by updating the key
-    /// state in the `Chip8` instance if necessary.
-    fn update_key_state(ctx: &Context, messages: &mut mpsc::Sender<Chip8Message>) {
-        let mut update = Vec::new();
-        if !ctx.wants_keyboard_input() {
-            ctx.input(|input| {
-                for (key, key_code) in KEY_MAP {
-                    update.push((key_code, input.keys_down.contains(&key)));
+    /// Handles the quick save state hotkeys, see [`SLOT_KEYS`]. Keys bound to
+    /// a Chip8 key are left to the game.
+    fn update_state_slots(
+        ctx: &Context,
+        bindings: &KeyBindings,
+        messages: &mut mpsc::Sender<Chip8Message>,
+    ) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        ctx.input(|input| {
+            for (slot, key) in (1..).zip(SLOT_KEYS) {
+                if input.key_pressed(key) && !bindings.contains(key) {
+                    let message = if input.modifiers.shift {
+                        Chip8Message::LoadStateSlot(slot)
+                    } else {
+                        Chip8Message::SaveStateSlot(slot)
+                    };
+                    let _ = messages.send(message);
                 }
+            }
+        });
+    }
+
+    /// Show the errors of the last Octo source that failed to assemble, if any,
+    /// until the window is closed.
+    fn update_assembly_errors(
+        ctx: &Context,
+        errors: &[assembler::Error],
+        messages: &mut mpsc::Sender<Chip8Message>,
+    ) {
+        if errors.is_empty() {
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new("Assembly Errors")
+            .open(&mut open)
+            .show(ctx, |ui| {
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for error in errors {
+                        ui.colored_label(ui.visuals().error_fg_color, error.to_string());
+                    }
+                });
             });
+        if !open {
+            let _ = messages.send(Chip8Message::ClearAssemblyErrors);
         }
-        if !update.is_empty() {
-            let _ = messages.send(Chip8Message::UpdateKeys(update));
+    }
+
+    /// Requests a rewind while the rewind hotkey is held down, unless it is
+    /// bound to a Chip8 key.
+    fn update_rewind(
+        ctx: &Context,
+        bindings: &KeyBindings,
+        messages: &mut mpsc::Sender<Chip8Message>,
+    ) {
+        if !ctx.wants_keyboard_input()
+            && !bindings.contains(REWIND_KEY)
+            && ctx.input(|input| input.key_down(REWIND_KEY))
+        {
+            let _ = messages.send(Chip8Message::Rewind);
+        }
+    }
+
+    /// Handles the shortcuts of the common controls, sending the same messages
+    /// as their buttons. The pause and step keys are ignored while they are
+    /// bound to a Chip8 key, so that they only reach the game.
+    fn update_shortcuts(&mut self, ctx: &Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+
+        let bindings = &self.key_bindings_window.bindings;
+        let (open_rom, reset, pause, step, reset_zoom) = ctx.input_mut(|input| {
+            (
+                input.consume_shortcut(&OPEN_ROM_SHORTCUT),
+                input.consume_shortcut(&RESET_SHORTCUT),
+                !bindings.contains(PAUSE_KEY) && input.consume_key(Modifiers::NONE, PAUSE_KEY),
+                !bindings.contains(STEP_KEY) && input.consume_key(Modifiers::NONE, STEP_KEY),
+                input.consume_shortcut(&RESET_ZOOM_SHORTCUT),
+            )
+        });
+
+        if reset_zoom {
+            self.screen_view.reset_zoom();
+            self.debug_view.screen_window.reset_zoom();
+        }
+
+        let messages = &self.message_channel.0;
+        if open_rom {
+            open_rom_with_dialog(messages.clone());
+        }
+        if reset {
+            let _ = messages.send(Chip8Message::ResetROM);
+        }
+        if pause {
+            let _ = messages.send(Chip8Message::TogglePause);
+        }
+        if step {
+            let steps = self.menu_panel.steps_per_click.max(1);
+            let _ = messages.send(Chip8Message::StepN(steps));
+        }
+    }
+
+    /// Toggles fullscreen when the fullscreen hotkey is pressed, unless it is
+    /// bound to a Chip8 key.
+    fn update_fullscreen(&mut self, ctx: &Context) {
+        if !ctx.wants_keyboard_input()
+            && !self.key_bindings_window.bindings.contains(FULLSCREEN_KEY)
+            && ctx.input(|input| input.key_pressed(FULLSCREEN_KEY))
+        {
+            self.toggle_fullscreen(ctx);
+        }
+    }
+
+    /// Enter or leave fullscreen. Leaving fullscreen restores the previous window size.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn toggle_fullscreen(&mut self, ctx: &Context) {
+        let (fullscreen, size) = ctx.input(|input| {
+            let viewport = input.viewport();
+            (
+                viewport.fullscreen.unwrap_or(false),
+                viewport.inner_rect.map(|rect| rect.size()),
+            )
+        });
+
+        if fullscreen {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(false));
+            if let Some(size) = self.windowed_size.take() {
+                ctx.send_viewport_cmd(egui::ViewportCommand::InnerSize(size));
+            }
+        } else {
+            self.windowed_size = size;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Fullscreen(true));
+        }
+    }
+
+    /// Enter or leave browser fullscreen. The browser restores the page layout itself.
+    #[cfg(target_arch = "wasm32")]
+    fn toggle_fullscreen(&mut self, _ctx: &Context) {
+        if let Err(e) = crate::web::toggle_fullscreen() {
+            log::error!("Failed to toggle fullscreen: {e}");
+        }
+    }
+
+    /// Start a new GIF recording, or stop the current one and ask the user where to save it.
+    fn toggle_recording(&mut self) {
+        let messages = self.message_channel.0.clone();
+        if self.menu_panel.recording {
+            let _ = messages.send(Chip8Message::StopRecording);
+            save_with_dialog("recording.gif", messages, Chip8Message::SaveRecording);
+        } else {
+            let options = self.config_window.recording_options;
+            let _ = messages.send(Chip8Message::StartRecording(options));
         }
+        self.menu_panel.recording = !self.menu_panel.recording;
+    }
+
+    /// Start recording the pressed keys, or stop the current input recording
+    /// and ask the user where to save it.
+    fn toggle_input_recording(&mut self) {
+        let messages = self.message_channel.0.clone();
+        if self.menu_panel.input_recording {
+            let _ = messages.send(Chip8Message::StopInputRecording);
+            save_with_dialog("input.json", messages, Chip8Message::SaveInputRecording);
+        } else {
+            let _ = messages.send(Chip8Message::StartInputRecording);
+        }
+    }
+
+    /// Handles key events by updating the key state in the `Chip8` instance. A key
+    /// is pressed if either its bound keyboard key is held down, or it is held in
+    /// `held_keys`, the gamepad and touch keypad state indexed by key code.
+    ///
+    /// Keys with their bit set in `released_keys` are released regardless, which
+    /// is used to pulse turbo keys.
+    fn update_key_state(
+        ctx: &Context,
+        bindings: &KeyBindings,
+        held_keys: [bool; 16],
+        released_keys: u16,
+        messages: &mut mpsc::Sender<Chip8Message>,
+    ) {
+        let keyboard = !ctx.wants_keyboard_input();
+        let update = ctx.input(|input| {
+            bindings
+                .iter()
+                .map(|(key, key_code)| {
+                    let key_down = keyboard && input.keys_down.contains(&key);
+                    let held = key_down || held_keys[usize::from(key_code)];
+                    (key_code, held && released_keys & (1 << key_code) == 0)
+                })
+                .collect()
+        });
+        let _ = messages.send(Chip8Message::UpdateKeys(update));
     }
 }
 
@@ -214,8 +855,32 @@ enum MenuPanelResponse {
     /// Indicates whether the config window should be toggled.
     ToggleConfigWindow,
 
+    /// Indicates whether the key bindings window should be toggled.
+    ToggleKeyBindingsWindow,
+
+    /// Indicates whether the on-screen touch keypad should be toggled.
+    ToggleTouchKeypad,
+
+    /// Indicates whether the performance overlay should be toggled.
+    TogglePerformanceOverlay,
+
+    /// Indicates whether the ROM info window should be toggled.
+    ToggleRomInfoWindow,
+
+    /// Indicates whether fullscreen should be toggled.
+    ToggleFullscreen,
+
+    /// Indicates that the screen view should zoom in by one step.
+    ZoomIn,
+
+    /// Indicates that the screen view should zoom out by one step.
+    ZoomOut,
+
+    /// Indicates that the screen view should fit the display to the window again.
+    ResetZoom,
+
     /// Indicates whether the registers window should be toggled.
-    ToggleResgistersWindow,
+    ToggleRegistersWindow,
 
     /// Indicates whether the stack window should be toggled.
     ToggleStackWindow,
@@ -232,22 +897,64 @@ enum MenuPanelResponse {
     /// Indicates whether the instructions window should be toggled.
     ToggleInstructionsWindow,
 
-    /// Indicates that the `Gui` state should be reset. This is `true`
-    /// when a new ROM has been loaded, or persisted state has been restored.
-    Reset,
+    /// Indicates whether the breakpoints window should be toggled.
+    ToggleBreakpointsWindow,
+
+    /// Indicates whether the disassembly window should be toggled.
+    ToggleDisassemblyWindow,
+
+    /// Indicates whether the memory window should be toggled.
+    ToggleMemoryWindow,
+
+    /// Indicates that the `Chip8` was cold reset, so the `Gui` should restore
+    /// the default quirks and colors to match it.
+    ColdReset,
 
     /// Indicates to the `Gui` to toggle the current view.
     ToggleView,
 
-    /// Indicates to the `Gui` to toggle its pause state.
-    TogglePause,
+    /// Indicates to the `Gui` to start or stop recording a GIF.
+    ToggleRecording,
+
+    /// Indicates to the `Gui` to start or stop recording the pressed keys.
+    ToggleInputRecording,
 }
 
 /// A menu panel intended to be placed near the top of the window,
 /// shows Ui widgets for selecting roms, saving state, etc.
 #[derive(Default, Deserialize, Serialize)]
 struct MenuPanel {
+    /// Mirrors the paused state of the `App`.
     paused: bool,
+    /// Mirrors whether the program of the `App` has halted.
+    #[serde(skip)]
+    halted: bool,
+    /// Mirrors whether the rewind buffer of the `App` has a snapshot to step back to.
+    #[serde(skip)]
+    can_step_back: bool,
+    /// Mirrors whether the `App` is tracing executed instructions.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(skip)]
+    tracing: bool,
+    #[serde(skip)]
+    recording: bool,
+    /// Mirrors whether the `App` is recording the pressed keys.
+    #[serde(skip)]
+    input_recording: bool,
+    /// Mirrors whether the `App` is playing back an input recording that can
+    /// desync because of random numbers.
+    #[serde(skip)]
+    replay_uses_random: bool,
+    /// The amount of cycles the "step" button executes at once.
+    steps_per_click: u32,
+    /// The URL typed into the "Open ROM from URL" menu.
+    #[cfg(target_arch = "wasm32")]
+    #[serde(skip)]
+    rom_url: String,
+    /// The character drawn for pixels that are on by "Copy Screen as Text", `#` if empty.
+    ascii_on: String,
+    /// The character drawn for pixels that are off by "Copy Screen as Text", a space if empty.
+    ascii_off: String,
 }
 
 impl MenuPanel {
@@ -258,6 +965,7 @@ impl MenuPanel {
         ctx: &Context,
         _frame: &mut eframe::Frame,
         view: &CurrentView,
+        recent_roms: &[RecentRom],
         mut messages: mpsc::Sender<Chip8Message>,
     ) -> MenuPanelResponse {
         let mut response = MenuPanelResponse::default();
@@ -265,19 +973,51 @@ impl MenuPanel {
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
-                    if ui.button("Open ROM").clicked() {
-                        let messages = messages.clone();
+                    let shortcut = ctx.format_shortcut(&OPEN_ROM_SHORTCUT);
+                    if ui
+                        .add(egui::Button::new("Open ROM").shortcut_text(shortcut))
+                        .clicked()
+                    {
+                        open_rom_with_dialog(messages.clone());
+                    }
 
-                        execute(async move {
-                            if let Some(file) = rfd::AsyncFileDialog::new().pick_file().await {
-                                let buff = file.read().await;
+                    #[cfg(target_arch = "wasm32")]
+                    ui.menu_button("Open ROM from URL", |ui| {
+                        ui.text_edit_singleline(&mut self.rom_url);
+                        let url = self.rom_url.trim();
+                        if ui
+                            .add_enabled(!url.is_empty(), egui::Button::new("Load"))
+                            .clicked()
+                        {
+                            load_rom_from_url(url.to_owned(), messages.clone());
+                            ui.close_menu();
+                        }
+                    });
 
-                                let _ = messages.send(Chip8Message::LoadRom(buff));
+                    ui.menu_button("Load Built-in ROM", |ui| {
+                        for rom in BUILTIN_ROMS {
+                            if ui.button(rom.name).clicked() {
+                                let _ = messages.send(Chip8Message::LoadRom(rom.data.to_vec()));
+                                ui.close_menu();
                             }
-                        });
+                        }
+                    });
 
-                        response = MenuPanelResponse::Reset;
-                    }
+                    ui.menu_button("Recent", |ui| {
+                        if recent_roms.is_empty() {
+                            ui.label("No recent ROMs");
+                        }
+
+                        for rom in recent_roms {
+                            if ui.button(&rom.name).clicked() {
+                                if let Some(data) = rom.read() {
+                                    let _ = messages.send(Chip8Message::AddRecentRom(rom.clone()));
+                                    send_rom(&rom.name, data, &messages);
+                                }
+                                ui.close_menu();
+                            }
+                        }
+                    });
 
                     ui.separator();
 
@@ -292,8 +1032,6 @@ impl MenuPanel {
                                     }
                                 }
                             });
-
-                            response = MenuPanelResponse::Reset;
                         }
 
                         if ui.button("Save State").clicked() {
@@ -311,6 +1049,107 @@ impl MenuPanel {
                         }
                     }
 
+                    ui.separator();
+
+                    if ui.button("Save Screenshot").clicked() {
+                        save_with_dialog(
+                            "screenshot.png",
+                            messages.clone(),
+                            Chip8Message::SaveScreenshot,
+                        );
+                    }
+
+                    if ui.button("Export Instruction Log").clicked() {
+                        save_with_dialog(
+                            "instructions.csv",
+                            messages.clone(),
+                            Chip8Message::ExportInstructionLog,
+                        );
+                    }
+
+                    if ui.button("Copy State as JSON").clicked() {
+                        let _ = messages.send(Chip8Message::CopyStateJson);
+                    }
+
+                    ui.menu_button("Copy Screen as Text", |ui| {
+                        ui.horizontal(|ui| {
+                            ui.label("On");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.ascii_on)
+                                    .hint_text("#")
+                                    .desired_width(24.0),
+                            );
+                            ui.label("Off");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.ascii_off)
+                                    .hint_text("space")
+                                    .desired_width(48.0),
+                            );
+                        });
+
+                        if ui.button("Copy").clicked() {
+                            let on = self.ascii_on.chars().next().unwrap_or('#');
+                            let off = self.ascii_off.chars().next().unwrap_or(' ');
+                            let _ = messages.send(Chip8Message::CopyScreenAscii { on, off });
+                            ui.close_menu();
+                        }
+                    });
+
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        let trace_label = if self.tracing {
+                            "Stop Trace"
+                        } else {
+                            "Start Trace"
+                        };
+                        let trace = ui.button(trace_label).on_hover_text(
+                            "Write every executed instruction and the registers before it \
+                            to a file. This slows down emulation.",
+                        );
+                        if trace.clicked() {
+                            if self.tracing {
+                                let _ = messages.send(Chip8Message::StopTrace);
+                            } else {
+                                let messages = messages.clone();
+                                save_with_dialog("trace.log", messages, Chip8Message::StartTrace);
+                            }
+                        }
+                    }
+
+                    let recording_label = if self.recording {
+                        "Stop Recording"
+                    } else {
+                        "Start Recording"
+                    };
+                    if ui.button(recording_label).clicked() {
+                        response = MenuPanelResponse::ToggleRecording;
+                    }
+
+                    ui.separator();
+
+                    let input_recording_label = if self.input_recording {
+                        "Stop Input Recording"
+                    } else {
+                        "Start Input Recording"
+                    };
+                    if ui.button(input_recording_label).clicked() {
+                        response = MenuPanelResponse::ToggleInputRecording;
+                    }
+
+                    let play = ui.button("Play Input Recording").on_hover_text(
+                        "Random numbers aren't recorded, so ROMs that use them can play back \
+                        differently",
+                    );
+                    if play.clicked() {
+                        let messages = messages.clone();
+                        execute(async move {
+                            if let Some(file) = rfd::AsyncFileDialog::new().pick_file().await {
+                                let data = file.read().await;
+                                let _ = messages.send(Chip8Message::PlayInputRecording(data));
+                            }
+                        });
+                    }
+
                     #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
                     {
                         ui.separator();
@@ -326,30 +1165,82 @@ impl MenuPanel {
                         response = MenuPanelResponse::ToggleConfigWindow;
                     }
 
-                    if let CurrentView::Debug = view {
-                        if ui.button("Registers").clicked() {
-                            response = MenuPanelResponse::ToggleResgistersWindow;
-                        }
+                    if ui.button("Key Bindings").clicked() {
+                        response = MenuPanelResponse::ToggleKeyBindingsWindow;
+                    }
 
-                        if ui.button("Stack").clicked() {
-                            response = MenuPanelResponse::ToggleStackWindow;
-                        }
+                    if ui.button("Touch Keypad").clicked() {
+                        response = MenuPanelResponse::ToggleTouchKeypad;
+                    }
 
-                        if ui.button("Screen").clicked() {
-                            response = MenuPanelResponse::ToggleScreenWindow;
-                        }
+                    if ui.button("ROM Info").clicked() {
+                        response = MenuPanelResponse::ToggleRomInfoWindow;
+                    }
 
-                        if ui.button("Timers").clicked() {
-                            response = MenuPanelResponse::ToggleTimersWindow;
-                        }
+                    if ui.button("Performance").clicked() {
+                        response = MenuPanelResponse::TogglePerformanceOverlay;
+                    }
 
-                        if ui.button("Key").clicked() {
+                    let fullscreen = ui.add(egui::Button::new("Fullscreen").shortcut_text("F11"));
+                    if fullscreen.clicked() {
+                        response = MenuPanelResponse::ToggleFullscreen;
+                    }
+
+                    if let CurrentView::Screen = view {
+                        ui.separator();
+
+                        let zoom_in = ui.button("Zoom In").on_hover_text("Or Ctrl+scroll");
+                        if zoom_in.clicked() {
+                            response = MenuPanelResponse::ZoomIn;
+                        }
+
+                        if ui.button("Zoom Out").clicked() {
+                            response = MenuPanelResponse::ZoomOut;
+                        }
+
+                        let shortcut = ui.ctx().format_shortcut(&RESET_ZOOM_SHORTCUT);
+                        let reset = ui.add(egui::Button::new("Reset Zoom").shortcut_text(shortcut));
+                        if reset.clicked() {
+                            response = MenuPanelResponse::ResetZoom;
+                        }
+                    }
+
+                    if let CurrentView::Debug = view {
+                        if ui.button("Registers").clicked() {
+                            response = MenuPanelResponse::ToggleRegistersWindow;
+                        }
+
+                        if ui.button("Stack").clicked() {
+                            response = MenuPanelResponse::ToggleStackWindow;
+                        }
+
+                        if ui.button("Screen").clicked() {
+                            response = MenuPanelResponse::ToggleScreenWindow;
+                        }
+
+                        if ui.button("Timers").clicked() {
+                            response = MenuPanelResponse::ToggleTimersWindow;
+                        }
+
+                        if ui.button("Key").clicked() {
                             response = MenuPanelResponse::ToggleKeyWindow;
                         }
 
                         if ui.button("Instructions").clicked() {
                             response = MenuPanelResponse::ToggleInstructionsWindow;
                         }
+
+                        if ui.button("Breakpoints").clicked() {
+                            response = MenuPanelResponse::ToggleBreakpointsWindow;
+                        }
+
+                        if ui.button("Disassembly").clicked() {
+                            response = MenuPanelResponse::ToggleDisassemblyWindow;
+                        }
+
+                        if ui.button("Memory").clicked() {
+                            response = MenuPanelResponse::ToggleMemoryWindow;
+                        }
                     }
                 });
 
@@ -386,32 +1277,101 @@ impl MenuPanel {
         ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
             Self::window_current_view_button(view, ui, response);
 
+            if self.halted {
+                ui.label(egui::RichText::new("\u{23F9} Halted").strong())
+                    .on_hover_text("The program is jumping to itself, it has most likely finished");
+            }
+
+            if self.replay_uses_random {
+                ui.colored_label(ui.visuals().warn_fg_color, "\u{26A0} Replay may desync")
+                    .on_hover_text(
+                        "The program draws random numbers, which input recordings don't \
+                        reproduce, so the playback can differ from the recorded session",
+                    );
+            }
+
             let play_pause_label = if self.paused {
                 "\u{23F5} Play"
             } else {
                 "\u{23F8} Pause"
             };
-            if ui.button(play_pause_label).clicked() {
+            let mut play_pause = egui::Button::new(play_pause_label);
+            if self.paused {
+                // make it obvious how to start a program that is waiting to run
+                play_pause = play_pause.fill(ui.visuals().selection.bg_fill);
+            }
+            let play_pause = ui
+                .add(play_pause)
+                .on_hover_text(format!("Toggle pause ({PAUSE_KEY:?})"));
+            if play_pause.clicked() {
                 let _ = messages.send(Chip8Message::TogglePause);
-                *response = MenuPanelResponse::TogglePause;
             }
 
-            if ui.button("\u{27A1} Step").clicked() {
-                let _ = messages.send(Chip8Message::Step);
+            let step = ui.button("\u{27A1} Step").on_hover_text(format!(
+                "Run the amount of cycles set to the left ({STEP_KEY:?})"
+            ));
+            if step.clicked() {
+                let _ = messages.send(Chip8Message::StepN(self.steps_per_click.max(1)));
+            }
+            ui.add(
+                egui::DragValue::new(&mut self.steps_per_click)
+                    .clamp_range(1..=10_000)
+                    .suffix("x"),
+            )
+            .on_hover_text("The amount of cycles to run per step");
+
+            if ui
+                .button("\u{2934} Step Out")
+                .on_hover_text("Run until the current subroutine returns")
+                .clicked()
+            {
+                let _ = messages.send(Chip8Message::StepOut);
+            }
+
+            if ui
+                .button("\u{21B7} Step Over")
+                .on_hover_text("Step, running any called subroutine until it returns")
+                .clicked()
+            {
+                let _ = messages.send(Chip8Message::StepOver);
+            }
+
+            let step_back = ui
+                .add_enabled(
+                    self.paused && self.can_step_back,
+                    egui::Button::new("\u{2B05} Step Back"),
+                )
+                .on_hover_text("Undo the last step")
+                .on_disabled_hover_text("Pause and step first, there is nothing to step back to");
+            if step_back.clicked() {
+                let _ = messages.send(Chip8Message::StepBack);
+            }
+
+            let rewind = ui
+                .button("\u{23EA} Rewind")
+                .on_hover_text("Hold to rewind (Backspace)");
+            if rewind.is_pointer_button_down_on() {
+                let _ = messages.send(Chip8Message::Rewind);
             }
 
-            if ui.button("\u{21BB} Reset").clicked() {
+            let cold_reset = ui.button("\u{23FB} Cold Reset").on_hover_text(
+                "Power cycle the interpreter, restoring the default quirks and colors",
+            );
+            if cold_reset.clicked() {
+                let _ = messages.send(Chip8Message::ColdReset);
+                *response = MenuPanelResponse::ColdReset;
+            }
+
+            let reset = ui.button("\u{21BB} Reset").on_hover_text(format!(
+                "Restart the ROM, keeping the current quirks and colors ({})",
+                ui.ctx().format_shortcut(&RESET_SHORTCUT)
+            ));
+            if reset.clicked() {
                 let _ = messages.send(Chip8Message::ResetROM);
-                *response = MenuPanelResponse::Reset;
             }
         });
     }
 
-    /// Toggle the `MenuPanel` paused state.
-    fn toggle_pause(&mut self) {
-        self.paused = !self.paused;
-    }
-
     /// Retrieves data from a file selected by a file dialog.
     /// Returns `None` if the chosen file cannot be read, or if the user
     /// cancelled the operation. Otherwise, returns the file's data as a `Vec<u8>`.
@@ -425,76 +1385,540 @@ impl MenuPanel {
     }
 }
 
+/// How the `Chip8` display is scaled to fit the available space.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+enum ScreenScaling {
+    /// Stretch the display to fill all of the available space.
+    #[default]
+    Stretch,
+
+    /// Keep the 2:1 aspect ratio of the display, letterboxing the rest.
+    KeepAspect,
+
+    /// Like [`ScreenScaling::KeepAspect`], but only scale by whole physical pixels
+    /// so that every `Chip8` pixel has the same size.
+    Integer,
+}
+
+impl ScreenScaling {
+    /// Every available [`ScreenScaling`], in the order they are displayed.
+    const ALL: [Self; 3] = [Self::Stretch, Self::KeepAspect, Self::Integer];
+
+    /// The part of `available` the display should be drawn in, for a
+    /// display of `size` pixels. The edges are snapped to physical pixels.
+    fn display_rect(self, available: Rect, size: egui::Vec2, pixels_per_point: f32) -> Rect {
+        let scale = (available.width() / size.x).min(available.height() / size.y);
+        let scale = match self {
+            Self::Stretch => return snap_to_pixels(available, pixels_per_point),
+            Self::KeepAspect => scale,
+            Self::Integer => (scale * pixels_per_point).floor().max(1.0) / pixels_per_point,
+        };
+        let rect = Rect::from_center_size(available.center(), size * scale);
+        snap_to_pixels(rect, pixels_per_point)
+    }
+}
+
+/// Round the corners of `rect` to the nearest physical pixels, so that its
+/// edges stay sharp at fractional scale factors, like non-100% browser zoom.
+///
+/// A `rect` that is a whole number of physical pixels large keeps its size.
+fn snap_to_pixels(rect: Rect, pixels_per_point: f32) -> Rect {
+    let snap = |pos: Pos2| (pos.to_vec2() * pixels_per_point).round().to_pos2() / pixels_per_point;
+    Rect::from_min_max(snap(rect.min), snap(rect.max))
+}
+
+impl std::fmt::Display for ScreenScaling {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Stretch => "Stretch",
+            Self::KeepAspect => "Keep Aspect Ratio",
+            Self::Integer => "Integer Scale",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A preset of display colors, selectable in the `ConfigWindow`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+enum Palette {
+    /// The colors picked by the user.
+    #[default]
+    Custom,
+    Green,
+    Amber,
+    BlackOnWhite,
+    GameBoy,
+}
+
+impl Palette {
+    /// Every available [`Palette`], in the order they are displayed.
+    const ALL: [Self; 5] = [
+        Self::Custom,
+        Self::Green,
+        Self::Amber,
+        Self::BlackOnWhite,
+        Self::GameBoy,
+    ];
+
+    /// The colors of this palette, or `None` for [`Palette::Custom`].
+    ///
+    /// The colors are indexed by the bit planes a pixel is lit in, so index `0` is
+    /// the background and index `1` the foreground. The remaining colors are for
+    /// the second plane of XO-Chip, which the `Chip8` doesn't support yet.
+    fn colors(self) -> Option<[Color32; 4]> {
+        let colors = match self {
+            Self::Custom => return None,
+            Self::Green => [
+                Color32::BLACK,
+                Color32::from_rgb(0x33, 0xFF, 0x66),
+                Color32::from_rgb(0x1A, 0x80, 0x33),
+                Color32::from_rgb(0xAA, 0xFF, 0xBB),
+            ],
+            Self::Amber => [
+                Color32::BLACK,
+                Color32::from_rgb(0xFF, 0xB0, 0x00),
+                Color32::from_rgb(0x80, 0x58, 0x00),
+                Color32::from_rgb(0xFF, 0xDD, 0x88),
+            ],
+            Self::BlackOnWhite => [
+                Color32::WHITE,
+                Color32::BLACK,
+                Color32::GRAY,
+                Color32::DARK_GRAY,
+            ],
+            Self::GameBoy => [
+                Color32::from_rgb(0x9B, 0xBC, 0x0F),
+                Color32::from_rgb(0x0F, 0x38, 0x0F),
+                Color32::from_rgb(0x8B, 0xAC, 0x0F),
+                Color32::from_rgb(0x30, 0x62, 0x30),
+            ],
+        };
+        Some(colors)
+    }
+}
+
+impl std::fmt::Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Custom => "Custom",
+            Self::Green => "Green on Black",
+            Self::Amber => "Amber",
+            Self::BlackOnWhite => "Black on White",
+            Self::GameBoy => "Game Boy",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The quirk flags of the `Chip8` that a [`QuirkProfile`] sets.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Quirks {
+    shift: bool,
+    vblank_wait: bool,
+}
+
+/// A preset of quirks matching a known platform, selectable in the `ConfigWindow`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+enum QuirkProfile {
+    /// The quirks picked by the user.
+    #[default]
+    Custom,
+    CosmacVip,
+    SuperChip,
+    XoChip,
+    Octo,
+}
+
+impl QuirkProfile {
+    /// Every available [`QuirkProfile`], in the order they are displayed.
+    const ALL: [Self; 5] = [
+        Self::Custom,
+        Self::CosmacVip,
+        Self::SuperChip,
+        Self::XoChip,
+        Self::Octo,
+    ];
+
+    /// The quirks of this profile, or `None` for [`QuirkProfile::Custom`].
+    ///
+    /// XO-Chip and the Octo defaults only differ in quirks the `Chip8`
+    /// doesn't support yet.
+    fn quirks(self) -> Option<Quirks> {
+        let quirks = match self {
+            Self::Custom => return None,
+            Self::CosmacVip => Quirks {
+                shift: false,
+                vblank_wait: true,
+            },
+            Self::SuperChip => Quirks {
+                shift: true,
+                vblank_wait: false,
+            },
+            Self::XoChip | Self::Octo => Quirks {
+                shift: false,
+                vblank_wait: false,
+            },
+        };
+        Some(quirks)
+    }
+}
+
+impl std::fmt::Display for QuirkProfile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Custom => "Custom",
+            Self::CosmacVip => "COSMAC VIP",
+            Self::SuperChip => "SuperChip",
+            Self::XoChip => "XO-Chip",
+            Self::Octo => "Octo Default",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The configurable options that change how the `ScreenView` draws the display.
+#[derive(Clone, Copy, Default)]
+struct ScreenOptions {
+    /// The current foreground color if the phosphor fade effect is enabled.
+    fade: Option<Color32>,
+    scaling: ScreenScaling,
+
+    /// The color of the letterbox around the display when it doesn't fill the screen.
+    background: Color32,
+
+    /// The darkness of the scanline overlay if it's enabled.
+    scanlines: Option<f32>,
+
+    /// The darkness of the pixel grid overlay if it's enabled.
+    pixel_grid: Option<f32>,
+
+    /// Show the pressed keys, and a banner while the `Chip8` is waiting for a key.
+    key_overlay: bool,
+}
+
 /// A screen panel that displays the Chip8 graphics state with a `Renderer`.
 /// Note that this component uses an [`egui::CentralPanel`], and should be added
 /// after all other panels.
-#[derive(Deserialize, Serialize)]
-struct ScreenView {}
+#[derive(Default, Deserialize, Serialize)]
+struct ScreenView {
+    /// The texture the `Chip8` graphics state is uploaded to each frame.
+    /// This is only reallocated when the resolution of the display changes.
+    #[serde(skip)]
+    texture: Option<TextureHandle>,
+
+    /// The displayed brightness of each pixel, from `0.0` (off) to `1.0` (fully lit).
+    /// Only used while the phosphor fade effect is enabled.
+    #[serde(skip)]
+    intensity: Vec<f32>,
+
+    /// The time left in seconds until each key disappears from the key overlay,
+    /// indexed by key code.
+    #[serde(skip)]
+    key_flash: [f32; 16],
+
+    /// The size of a `Chip8` pixel in physical pixels, or `None` to fit the
+    /// display to the view with the selected [`ScreenScaling`].
+    zoom: Option<u32>,
+
+    /// The zoom closest to the fitted display, which zooming starts from.
+    #[serde(skip)]
+    fit_zoom: u32,
+
+    /// The Ctrl+scroll input that hasn't added up to a whole zoom step yet.
+    #[serde(skip)]
+    zoom_input: f32,
+}
 
 impl ScreenView {
+    /// Zoom in (or out, if negative) by `steps` physical pixels per `Chip8` pixel.
+    fn zoom_by(&mut self, steps: i32) {
+        let zoom = self.zoom.unwrap_or(self.fit_zoom);
+        self.zoom = Some(zoom.saturating_add_signed(steps).clamp(1, MAX_ZOOM));
+    }
+
+    /// Go back to fitting the display to the view.
+    fn reset_zoom(&mut self) {
+        self.zoom = None;
+        self.zoom_input = 0.0;
+    }
+
+    /// Zoom with Ctrl+scroll (or pinch) while the pointer is over the view.
+    fn update_zoom_input(&mut self, ui: &Ui, hovered: bool) {
+        let delta = ui.input(|input| input.zoom_delta());
+        if !hovered || delta == 1.0 {
+            return;
+        }
+
+        self.zoom_input += delta.ln();
+        let steps = (self.zoom_input / ZOOM_INPUT_STEP).trunc();
+        if steps != 0.0 {
+            self.zoom_input -= steps * ZOOM_INPUT_STEP;
+            self.zoom_by(steps as i32);
+        }
+    }
+
     /// Update and draw this `ScreenView`. This creates a central panel, therefore it
     /// should be called after all other panels are drawn.
-    fn update(ctx: &Context, chip8: &Chip8) {
+    fn update(&mut self, ctx: &Context, chip8: &Chip8, options: &ScreenOptions) {
         egui::CentralPanel::default()
             .frame(egui::Frame::default().inner_margin(egui::vec2(0.0, 0.0)))
             .show(ctx, |ui| {
-                Self::draw_chip8_renderer(ui, chip8);
+                self.draw_chip8_renderer(ui, chip8, options);
             });
     }
 
     /// Draw the `Chip8` graphics state onto a `Ui` object.
     ///
-    /// This uses the rest of the available size in the `Ui`.
-    fn draw_chip8_renderer(ui: &mut Ui, chip8: &Chip8) {
+    /// This uses the rest of the available size in the `Ui`. If the fade color of
+    /// `options` is the current foreground color, pixels that are turned off will
+    /// decay towards the background over a few frames instead of switching instantly.
+    fn draw_chip8_renderer(&mut self, ui: &mut Ui, chip8: &Chip8, options: &ScreenOptions) {
+        let size = [chip8::graphics::WIDTH, chip8::graphics::HEIGHT];
+        let rgb = chip8.bus.graphics.as_rgb8();
+        let image = match options.fade {
+            Some(foreground) => self.faded_image(ui.ctx(), size, &rgb, foreground),
+            None => {
+                self.intensity.clear();
+                ColorImage::from_rgb(size, &rgb)
+            }
+        };
+
+        // Reuse the existing texture unless the resolution changed
+        match &mut self.texture {
+            Some(texture) if texture.size() == image.size => {
+                texture.set(image, TextureOptions::NEAREST);
+            }
+            _ => {
+                self.texture = Some(ui.ctx().load_texture(
+                    "chip8_screen",
+                    image,
+                    TextureOptions::NEAREST,
+                ));
+            }
+        }
+
+        if options.key_overlay {
+            self.update_key_flash(ui.ctx(), chip8);
+        }
+
+        let Some(texture) = &self.texture else {
+            return;
+        };
+        let texture_id = texture.id();
+        let texture_size = texture.size_vec2();
+        let pixels_per_point = ui.ctx().pixels_per_point();
+
         ui.with_layout(
             egui::Layout::top_down_justified(egui::Align::Center),
             |ui| {
                 egui::Frame::canvas(ui.style()).show(ui, |ui| {
-                    let (rect, _) = ui.allocate_exact_size(
-                        ui.available_size(),
-                        egui::Sense::focusable_noninteractive(),
-                    );
-
-                    // Define an array of sorted RGB values
-                    let colors = chip8.bus.graphics.as_rgb8();
-                    let pixel_height = rect.size().x / chip8::graphics::WIDTH as f32;
-                    let pixel_width = rect.size().y / chip8::graphics::HEIGHT as f32;
-
-                    // Create a list of rectangles to draw
-                    let mut rects = Vec::new();
-                    for (i, color) in colors.chunks(3).enumerate() {
-                        let row = i / chip8::graphics::WIDTH;
-                        let col = i % chip8::graphics::WIDTH;
-                        let rect_x = rect.left() + col as f32 * pixel_height;
-                        let rect_y = rect.top() + row as f32 * pixel_width;
-                        let color = Color32::from_rgb(color[0], color[1], color[2]);
-                        let color_rect = Rect::from_min_max(
-                            Pos2 {
-                                x: rect_x,
-                                y: rect_y,
-                            },
-                            Pos2 {
-                                x: rect_x + pixel_height,
-                                y: rect_y + pixel_width,
-                            },
+                    let view = ui.available_rect_before_wrap();
+                    let fit = options
+                        .scaling
+                        .display_rect(view, texture_size, pixels_per_point);
+                    self.fit_zoom = (fit.width() * pixels_per_point / texture_size.x)
+                        .round()
+                        .max(1.0) as u32;
+                    self.update_zoom_input(ui, ui.rect_contains_pointer(view));
+
+                    let Some(zoom) = self.zoom else {
+                        let (available, _) = ui.allocate_exact_size(
+                            view.size(),
+                            egui::Sense::focusable_noninteractive(),
                         );
-                        rects.push((color_rect, color));
-                    }
-
-                    // Draw the list of rectangles
-                    let painter = ui.painter();
-                    painter.extend(rects.iter().map(|(rect, color)| {
-                        egui::Shape::Rect(RectShape::new(
-                            *rect,
-                            Rounding::ZERO,
-                            *color,
-                            Stroke::new(1.0, *color),
-                        ))
-                    }));
+                        self.draw_display(ui, available, fit, texture_id, chip8, options);
+                        return;
+                    };
+
+                    // Center the zoomed display, and pan around it if it doesn't fit
+                    egui::ScrollArea::both()
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            let size = texture_size * zoom as f32 / pixels_per_point;
+                            let (available, _) = ui.allocate_exact_size(
+                                ui.available_size().max(size),
+                                egui::Sense::focusable_noninteractive(),
+                            );
+                            let rect = snap_to_pixels(
+                                Rect::from_center_size(available.center(), size),
+                                pixels_per_point,
+                            );
+                            self.draw_display(ui, available, rect, texture_id, chip8, options);
+                        });
                 });
             },
         );
     }
+
+    /// Draw the display texture into `rect`, with its overlays, filling the
+    /// rest of `available` with the background color.
+    fn draw_display(
+        &self,
+        ui: &Ui,
+        available: Rect,
+        rect: Rect,
+        texture_id: egui::TextureId,
+        chip8: &Chip8,
+        options: &ScreenOptions,
+    ) {
+        let size = [chip8::graphics::WIDTH, chip8::graphics::HEIGHT];
+        if rect != available {
+            ui.painter().rect_filled(available, 0.0, options.background);
+        }
+        ui.painter().image(
+            texture_id,
+            rect,
+            Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0)),
+            Color32::WHITE,
+        );
+        Self::draw_overlays(ui.painter(), rect, size, options);
+        if options.key_overlay {
+            let waiting = chip8.bus.input.waiting();
+            Self::draw_key_overlay(ui.painter(), rect, &self.key_flash, waiting);
+        }
+    }
+
+    /// Draw the enabled scanline and pixel grid overlays over the display in `rect`,
+    /// which is `size` pixels large. The overlays are sized relative to the
+    /// `Chip8` pixels, so they look the same at any window size.
+    fn draw_overlays(
+        painter: &egui::Painter,
+        rect: Rect,
+        [width, height]: [usize; 2],
+        options: &ScreenOptions,
+    ) {
+        let pixel_width = rect.width() / width as f32;
+        let pixel_height = rect.height() / height as f32;
+
+        // darken the bottom half of every row of pixels
+        if let Some(intensity) = options.scanlines {
+            let color = Color32::from_black_alpha((intensity * 255.0) as u8);
+            for row in 0..height {
+                let top = rect.top() + (row as f32 + 0.5) * pixel_height;
+                let line = Rect::from_x_y_ranges(rect.x_range(), top..=top + pixel_height / 2.0);
+                painter.rect_filled(line, 0.0, color);
+            }
+        }
+
+        if let Some(intensity) = options.pixel_grid {
+            let stroke =
+                egui::Stroke::new(1.0, Color32::from_black_alpha((intensity * 255.0) as u8));
+            for column in 1..width {
+                let x = rect.left() + column as f32 * pixel_width;
+                painter.vline(x, rect.y_range(), stroke);
+            }
+            for row in 1..height {
+                let y = rect.top() + row as f32 * pixel_height;
+                painter.hline(rect.x_range(), y, stroke);
+            }
+        }
+    }
+
+    /// Restart the flash of every key pressed on `chip8`, and fade out the rest.
+    fn update_key_flash(&mut self, ctx: &Context, chip8: &Chip8) {
+        let dt = ctx.input(|input| input.stable_dt);
+        for (code, flash) in (0..16).zip(&mut self.key_flash) {
+            *flash = if chip8.bus.input.is_key_pressed(code) {
+                KEY_FLASH_TIME
+            } else {
+                (*flash - dt).max(0.0)
+            };
+        }
+    }
+
+    /// Draw the recently pressed keys in the bottom left corner of the display in
+    /// `rect`, and a banner at the top if the `Chip8` is `waiting` for a key press.
+    fn draw_key_overlay(painter: &egui::Painter, rect: Rect, key_flash: &[f32; 16], waiting: bool) {
+        const MARGIN: f32 = 8.0;
+
+        if waiting {
+            let pos = rect.center_top() + egui::vec2(0.0, MARGIN);
+            Self::draw_label(
+                painter,
+                pos,
+                egui::Align2::CENTER_TOP,
+                "Waiting for key\u{2026}",
+                1.0,
+            );
+        }
+
+        let mut pos = rect.left_bottom() + egui::vec2(MARGIN, -MARGIN);
+        for (code, &flash) in key_flash.iter().enumerate() {
+            if flash > 0.0 {
+                let text = format!("{code:X}");
+                let opacity = flash / KEY_FLASH_TIME;
+                let label =
+                    Self::draw_label(painter, pos, egui::Align2::LEFT_BOTTOM, &text, opacity);
+                pos.x = label.right() + MARGIN;
+            }
+        }
+    }
+
+    /// Draw `text` on a dark rounded background, returning the area of the background.
+    fn draw_label(
+        painter: &egui::Painter,
+        pos: Pos2,
+        anchor: egui::Align2,
+        text: &str,
+        opacity: f32,
+    ) -> Rect {
+        const PADDING: f32 = 4.0;
+
+        // reserve a shape below the text, since its size is only known once it's drawn
+        let background = painter.add(egui::Shape::Noop);
+        let offset = anchor.to_sign() * -PADDING;
+        let text = painter.text(
+            pos + offset,
+            anchor,
+            text,
+            egui::FontId::monospace(16.0),
+            Color32::WHITE.gamma_multiply(opacity),
+        );
+
+        let rect = text.expand(PADDING);
+        let color = Color32::from_black_alpha((opacity * 192.0) as u8);
+        painter.set(background, egui::Shape::rect_filled(rect, PADDING, color));
+        rect
+    }
+
+    /// Build an image from the given RGB data where every pixel that is not lit
+    /// with `foreground` keeps a decaying amount of its previous brightness.
+    ///
+    /// This is purely cosmetic, the `Chip8` still uses the real pixel state for collisions.
+    fn faded_image(
+        &mut self,
+        ctx: &Context,
+        size: [usize; 2],
+        rgb: &[u8],
+        foreground: Color32,
+    ) -> ColorImage {
+        let decay = FADE_DECAY.powf(ctx.input(|input| input.stable_dt) * 60.0);
+        self.intensity.resize(size[0] * size[1], 0.0);
+
+        let pixels = rgb
+            .chunks_exact(3)
+            .zip(&mut self.intensity)
+            .map(|(color, intensity)| {
+                let color = Color32::from_rgb(color[0], color[1], color[2]);
+                *intensity = if color == foreground {
+                    1.0
+                } else {
+                    *intensity * decay
+                };
+
+                let mix = |from: u8, to: u8| {
+                    egui::lerp(f32::from(from)..=f32::from(to), *intensity).round() as u8
+                };
+                Color32::from_rgb(
+                    mix(color.r(), foreground.r()),
+                    mix(color.g(), foreground.g()),
+                    mix(color.b(), foreground.b()),
+                )
+            })
+            .collect();
+
+        ColorImage { size, pixels }
+    }
 }
 
 /// A configuration window which allows the user to customize
@@ -502,11 +1926,33 @@ impl ScreenView {
 #[derive(Deserialize, Serialize)]
 struct ConfigWindow {
     visible: bool,
+    palette: Palette,
     foreground_rgb: Color32,
     background_rgb: Color32,
-    steps_per_frame: u32,
+    /// The foreground and background colors last picked by the user, restored
+    /// when switching back to [`Palette::Custom`].
+    custom_colors: [Color32; 2],
+    cycles_per_second: u32,
+    quirk_profile: QuirkProfile,
     shift_quirk_enabled: bool,
     vblank_wait_enabled: bool,
+    invalid_opcode_policy: InvalidOpcodePolicy,
+    fade_enabled: bool,
+    screen_scaling: ScreenScaling,
+    scanlines_enabled: bool,
+    scanline_intensity: f32,
+    pixel_grid_enabled: bool,
+    pixel_grid_intensity: f32,
+    key_overlay_enabled: bool,
+    audio_settings: audio::Settings,
+    recording_options: RecordingOptions,
+    rewind_capacity: usize,
+    reject_mismatched_states: bool,
+    start_paused: bool,
+    /// The keys that pulse while held, where bit `n` is set for key `n`.
+    turbo_keys: u16,
+    /// How many times per second turbo keys are pressed while held.
+    turbo_rate: u32,
 }
 
 impl Default for ConfigWindow {
@@ -522,109 +1968,894 @@ impl Default for ConfigWindow {
         };
         Self {
             visible: false,
+            palette: Palette::default(),
             foreground_rgb,
             background_rgb,
-            steps_per_frame: crate::app::DEFAULT_STEPS_PER_FRAME,
+            custom_colors: [foreground_rgb, background_rgb],
+            cycles_per_second: crate::app::DEFAULT_CYCLES_PER_SECOND,
+            quirk_profile: QuirkProfile::default(),
             shift_quirk_enabled: false,
             vblank_wait_enabled: false,
+            invalid_opcode_policy: InvalidOpcodePolicy::default(),
+            fade_enabled: false,
+            screen_scaling: ScreenScaling::default(),
+            scanlines_enabled: false,
+            scanline_intensity: DEFAULT_SCANLINE_INTENSITY,
+            pixel_grid_enabled: false,
+            pixel_grid_intensity: DEFAULT_PIXEL_GRID_INTENSITY,
+            key_overlay_enabled: true,
+            audio_settings: audio::Settings::default(),
+            recording_options: RecordingOptions::default(),
+            rewind_capacity: crate::app::DEFAULT_REWIND_FRAMES,
+            reject_mismatched_states: false,
+            start_paused: false,
+            turbo_keys: 0,
+            turbo_rate: DEFAULT_TURBO_RATE,
+        }
+    }
+}
+
+impl ConfigWindow {
+    /// Update and render the `ConfigWindow` to the given `Context`.
+    /// This will append any GUI messages to `messages` if the `Chip8` state should be updated.
+    ///
+    /// If `audio_available` is `false`, the audio settings are shown with a note
+    /// that the app is running without sound.
+    fn update(
+        &mut self,
+        ctx: &Context,
+        audio_available: bool,
+        messages: &mut mpsc::Sender<Chip8Message>,
+    ) {
+        egui::Window::new("Config")
+            .open(&mut self.visible)
+            .show(ctx, |ui| {
+                egui::Grid::new("config_grid").show(ui, |ui| {
+                    // palette selector
+                    ui.label("Palette");
+                    let mut palette = self.palette;
+                    egui::ComboBox::from_id_source("palette")
+                        .selected_text(palette.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in Palette::ALL {
+                                ui.selectable_value(&mut palette, option, option.to_string());
+                            }
+                        });
+                    if palette != self.palette {
+                        self.palette = palette;
+                        [self.foreground_rgb, self.background_rgb] = match palette.colors() {
+                            Some([background, foreground, ..]) => [foreground, background],
+                            None => self.custom_colors,
+                        };
+                        let [foreground, background] = [self.foreground_rgb, self.background_rgb];
+                        let _ = messages.send(Chip8Message::SetForegroundColor(foreground));
+                        let _ = messages.send(Chip8Message::SetBackgroundColor(background));
+                    }
+                    ui.end_row();
+
+                    // foreground color selector
+                    ui.label("Foreground Color");
+                    if ui
+                        .color_edit_button_srgba(&mut self.foreground_rgb)
+                        .changed()
+                    {
+                        self.palette = Palette::Custom;
+                        self.custom_colors = [self.foreground_rgb, self.background_rgb];
+                        let _ = messages.send(Chip8Message::SetForegroundColor(self.foreground_rgb));
+                    }
+                    ui.end_row();
+
+                    // background color selector
+                    ui.label("Background Color");
+                    if ui
+                        .color_edit_button_srgba(&mut self.background_rgb)
+                        .changed()
+                    {
+                        self.palette = Palette::Custom;
+                        self.custom_colors = [self.foreground_rgb, self.background_rgb];
+                        let _ = messages.send(Chip8Message::SetBackgroundColor(self.background_rgb));
+                    }
+                    ui.end_row();
+
+                    // cycle rate selector
+                    ui.label("Cycles Per Second");
+                    let drag = egui::DragValue::new(&mut self.cycles_per_second).speed(10);
+                    if ui.add(drag).changed() {
+                        let message = Chip8Message::SetCyclesPerSecond(self.cycles_per_second);
+                        let _ = messages.send(message);
+                    }
+                    ui.end_row();
+
+                    // quirk profile selector
+                    ui.label("Quirk Profile");
+                    let mut profile = self.quirk_profile;
+                    egui::ComboBox::from_id_source("quirk_profile")
+                        .selected_text(profile.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in QuirkProfile::ALL {
+                                ui.selectable_value(&mut profile, option, option.to_string());
+                            }
+                        })
+                        .response
+                        .on_hover_text("Set all quirks to match a known platform.");
+                    if profile != self.quirk_profile {
+                        self.quirk_profile = profile;
+                        if let Some(quirks) = profile.quirks() {
+                            self.shift_quirk_enabled = quirks.shift;
+                            self.vblank_wait_enabled = quirks.vblank_wait;
+                            let _ = messages.send(Chip8Message::SetShiftQuirk(quirks.shift));
+                            let _ = messages.send(Chip8Message::SetVblankWait(quirks.vblank_wait));
+                        }
+                    }
+                    ui.end_row();
+
+                    ui.label("Enable Shift Quirk");
+                    let shift_quirk_checkbox = ui.checkbox(&mut self.shift_quirk_enabled, "");
+                    if shift_quirk_checkbox.changed() {
+                        self.quirk_profile = QuirkProfile::Custom;
+                        let _ = messages.send(Chip8Message::SetShiftQuirk(self.shift_quirk_enabled));
+                    }
+                    shift_quirk_checkbox.on_hover_text(
+                        "Enable/disable the shift quirk in the interpreter. \
+                        Try toggling this if a program isn't working as expected.",
+                    );
+                    ui.end_row();
+
+                    ui.label("Enable VBLANK Wait");
+                    let vblank_wait_checkbox = ui.checkbox(&mut self.vblank_wait_enabled, "");
+                    if vblank_wait_checkbox.changed() {
+                        self.quirk_profile = QuirkProfile::Custom;
+                        let _ = messages.send(Chip8Message::SetVblankWait(self.vblank_wait_enabled));
+                    }
+                    vblank_wait_checkbox.on_hover_text(
+                        "Enable/disable waiting for the vertical blank interrupt before drawing a sprite. \
+                        This will limit the amount of sprite draw calls to 60 calls per second."
+                    );
+                    ui.end_row();
+
+                    ui.label("On Invalid Opcode");
+                    let mut policy = self.invalid_opcode_policy;
+                    egui::ComboBox::from_id_source("invalid_opcode_policy")
+                        .selected_text(policy.to_string())
+                        .show_ui(ui, |ui| {
+                            for option in InvalidOpcodePolicy::ALL {
+                                ui.selectable_value(&mut policy, option, option.to_string());
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "What to do when the program reaches a word that isn't an \
+                            instruction, such as the empty memory after a program that \
+                            ran off its end.",
+                        );
+                    if policy != self.invalid_opcode_policy {
+                        self.invalid_opcode_policy = policy;
+                        let _ = messages.send(Chip8Message::SetInvalidOpcodePolicy(policy));
+                    }
+                    ui.end_row();
+
+                    ui.label("Enable Phosphor Fade");
+                    ui.checkbox(&mut self.fade_enabled, "").on_hover_text(
+                        "Let pixels fade out over a few frames instead of turning off instantly. \
+                        This reduces the flicker caused by sprites being redrawn.",
+                    );
+                    ui.end_row();
+
+                    ui.label("Screen Scaling");
+                    egui::ComboBox::from_id_source("screen_scaling")
+                        .selected_text(self.screen_scaling.to_string())
+                        .show_ui(ui, |ui| {
+                            for scaling in ScreenScaling::ALL {
+                                let text = scaling.to_string();
+                                ui.selectable_value(&mut self.screen_scaling, scaling, text);
+                            }
+                        })
+                        .response
+                        .on_hover_text(
+                            "Keep the aspect ratio of the display, optionally only scaling \
+                            by whole pixels to avoid uneven pixel sizes.",
+                        );
+                    ui.end_row();
+
+                    ui.label("Scanlines");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.scanlines_enabled, "");
+                        let intensity = egui::Slider::new(&mut self.scanline_intensity, 0.0..=1.0);
+                        ui.add_enabled(self.scanlines_enabled, intensity);
+                    });
+                    ui.end_row();
+
+                    ui.label("Pixel Grid");
+                    ui.horizontal(|ui| {
+                        ui.checkbox(&mut self.pixel_grid_enabled, "");
+                        let intensity =
+                            egui::Slider::new(&mut self.pixel_grid_intensity, 0.0..=1.0);
+                        ui.add_enabled(self.pixel_grid_enabled, intensity);
+                    });
+                    ui.end_row();
+
+                    ui.label("Key Overlay");
+                    ui.checkbox(&mut self.key_overlay_enabled, "").on_hover_text(
+                        "Briefly show the pressed keys over the display, and a banner \
+                        while the program waits for a key press.",
+                    );
+                    ui.end_row();
+
+                    if !audio_available {
+                        ui.label("Audio");
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            "Unavailable, running without sound",
+                        );
+                        ui.end_row();
+                    }
+
+                    ui.label("Volume");
+                    let volume = egui::Slider::new(&mut self.audio_settings.volume, 0.0..=1.0);
+                    if ui.add(volume).changed() {
+                        let _ = messages.send(Chip8Message::SetVolume(self.audio_settings.volume));
+                    }
+                    ui.end_row();
+
+                    ui.label("Mute");
+                    if ui.checkbox(&mut self.audio_settings.muted, "").changed() {
+                        let _ = messages.send(Chip8Message::SetMuted(self.audio_settings.muted));
+                    }
+                    ui.end_row();
+
+                    ui.label("Beep Frequency");
+                    let frequency = egui::DragValue::new(&mut self.audio_settings.frequency)
+                        .clamp_range(audio::FREQUENCY_RANGE)
+                        .suffix(" Hz");
+                    if ui.add(frequency).changed() {
+                        let frequency = self.audio_settings.frequency;
+                        let _ = messages.send(Chip8Message::SetBeepFrequency(frequency));
+                    }
+                    ui.end_row();
+
+                    ui.label("Waveform");
+                    egui::ComboBox::from_id_source("waveform")
+                        .selected_text(self.audio_settings.waveform.to_string())
+                        .show_ui(ui, |ui| {
+                            for waveform in Waveform::ALL {
+                                let selected = &mut self.audio_settings.waveform;
+                                let text = waveform.to_string();
+                                let option = ui.selectable_value(selected, waveform, text);
+                                if option.changed() {
+                                    let _ = messages.send(Chip8Message::SetWaveform(waveform));
+                                }
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label("Rewind Frames");
+                    let rewind_capacity = egui::DragValue::new(&mut self.rewind_capacity);
+                    let rewind_capacity = ui.add(rewind_capacity);
+                    if rewind_capacity.changed() {
+                        let _ = messages.send(Chip8Message::SetRewindCapacity(self.rewind_capacity));
+                    }
+                    rewind_capacity.on_hover_text(
+                        "The amount of frames that can be rewound. \
+                        Each frame takes roughly 10-20KB of memory, set to 0 to disable rewinding.",
+                    );
+                    ui.end_row();
+
+                    ui.label("Refuse Mismatched States");
+                    let reject = ui.checkbox(&mut self.reject_mismatched_states, "");
+                    if reject.changed() {
+                        let reject = self.reject_mismatched_states;
+                        let _ = messages.send(Chip8Message::SetRejectMismatchedStates(reject));
+                    }
+                    reject.on_hover_text(
+                        "Refuse to load save states made with a different ROM, \
+                        instead of only showing a warning.",
+                    );
+                    ui.end_row();
+
+                    ui.label("Start Paused");
+                    let start_paused = ui.checkbox(&mut self.start_paused, "");
+                    if start_paused.changed() {
+                        let _ = messages.send(Chip8Message::SetStartPaused(self.start_paused));
+                    }
+                    start_paused.on_hover_text(
+                        "Pause whenever a ROM is loaded or reset, \
+                        so breakpoints can be set before it runs.",
+                    );
+                    ui.end_row();
+
+                    ui.label("Turbo Keys");
+                    ui.horizontal_wrapped(|ui| {
+                        for key_code in 0..16 {
+                            let bit = 1 << key_code;
+                            let enabled = self.turbo_keys & bit != 0;
+                            let toggle = ui.selectable_label(enabled, format!("{key_code:X}"));
+                            if toggle.clicked() {
+                                self.turbo_keys ^= bit;
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Keys that are repeatedly pressed and released while held.");
+                    ui.end_row();
+
+                    ui.label("Turbo Rate");
+                    let turbo_rate = egui::DragValue::new(&mut self.turbo_rate);
+                    ui.add(turbo_rate.clamp_range(1..=30).suffix(" Hz"));
+                    ui.end_row();
+
+                    ui.label("GIF Scale");
+                    let scale = egui::DragValue::new(&mut self.recording_options.scale);
+                    ui.add(scale.clamp_range(1..=16));
+                    ui.end_row();
+
+                    ui.label("GIF Frame Rate");
+                    let frame_rate = egui::DragValue::new(&mut self.recording_options.frame_rate);
+                    ui.add(frame_rate.clamp_range(1..=50));
+                    ui.end_row();
+
+                    ui.label("GIF Frame Limit");
+                    let max_frames = egui::DragValue::new(&mut self.recording_options.max_frames);
+                    ui.add(max_frames).on_hover_text(
+                        "The maximum amount of frames kept in memory while recording. \
+                        Each frame takes around 6KB.",
+                    );
+                    ui.end_row();
+                });
+            });
+    }
+
+    /// Restore the colors and quirks to their defaults, matching a freshly created `Chip8`.
+    fn reset_chip8_settings(&mut self) {
+        let defaults = Self::default();
+        self.palette = defaults.palette;
+        self.foreground_rgb = defaults.foreground_rgb;
+        self.background_rgb = defaults.background_rgb;
+        self.custom_colors = defaults.custom_colors;
+        self.quirk_profile = defaults.quirk_profile;
+        self.shift_quirk_enabled = defaults.shift_quirk_enabled;
+        self.vblank_wait_enabled = defaults.vblank_wait_enabled;
+    }
+
+    /// Mirror the settings given on the command line, which the `App` has
+    /// already applied.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn apply_args(&mut self, args: &Args) {
+        if let Some(speed) = args.speed {
+            self.cycles_per_second = speed;
+        }
+        if args.shift_quirk || args.vblank_wait {
+            self.quirk_profile = QuirkProfile::Custom;
+        }
+        self.shift_quirk_enabled |= args.shift_quirk;
+        self.vblank_wait_enabled |= args.vblank_wait;
+
+        if args.fg.is_some() || args.bg.is_some() {
+            self.palette = Palette::Custom;
+            self.foreground_rgb = args.fg.unwrap_or(self.foreground_rgb);
+            self.background_rgb = args.bg.unwrap_or(self.background_rgb);
+            self.custom_colors = [self.foreground_rgb, self.background_rgb];
+        }
+    }
+
+    /// The turbo keys that should be released this frame, where bit `n` is set
+    /// for key `n`. Turbo keys are released for the second half of each pulse.
+    fn turbo_released_keys(&self, ctx: &Context) -> u16 {
+        let pulses = ctx.input(|input| input.time) * f64::from(self.turbo_rate.max(1));
+        if pulses.fract() < 0.5 {
+            0
+        } else {
+            self.turbo_keys
+        }
+    }
+
+    /// The options the `ScreenView` should be drawn with.
+    fn screen_options(&self) -> ScreenOptions {
+        ScreenOptions {
+            fade: self.fade_enabled.then_some(self.foreground_rgb),
+            scaling: self.screen_scaling,
+            background: self.background_rgb,
+            scanlines: self.scanlines_enabled.then_some(self.scanline_intensity),
+            pixel_grid: self.pixel_grid_enabled.then_some(self.pixel_grid_intensity),
+            key_overlay: self.key_overlay_enabled,
+        }
+    }
+
+    /// Toggle the visibility of this `ConfigWindow`,
+    fn toggle_visibility(&mut self) {
+        self.visible = !self.visible;
+    }
+}
+
+/// Key bindings from the keyboard to the 16 Chip8 keys, indexed by key code.
+#[derive(Clone, Deserialize, Serialize)]
+struct KeyBindings([Key; 16]);
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        let mut keys = [Key::X; 16];
+        for (key, key_code) in DEFAULT_KEY_MAP {
+            keys[usize::from(key_code)] = key;
+        }
+        Self(keys)
+    }
+}
+
+impl KeyBindings {
+    /// Iterate over each bound key and the Chip8 key code it maps to.
+    fn iter(&self) -> impl Iterator<Item = (Key, u8)> + '_ {
+        (0..).zip(self.0).map(|(key_code, key)| (key, key_code))
+    }
+
+    /// Returns `true` if `key` is bound to any Chip8 key.
+    fn contains(&self, key: Key) -> bool {
+        self.0.contains(&key)
+    }
+
+    /// Iterate over the bound keys that are also used by a hotkey without
+    /// modifiers. These hotkeys are disabled, so that the keys only reach the game.
+    fn hotkeys(&self) -> impl Iterator<Item = Key> + '_ {
+        let hotkeys = [PAUSE_KEY, STEP_KEY, REWIND_KEY, FULLSCREEN_KEY];
+        self.0
+            .iter()
+            .copied()
+            .filter(move |key| hotkeys.contains(key) || SLOT_KEYS.contains(key))
+    }
+}
+
+/// An on-screen keypad for devices without a physical keyboard, such as phones
+/// and tablets, laid out like the Chip8 keypad.
+#[derive(Default, Deserialize, Serialize)]
+struct TouchKeypad {
+    /// Whether the keypad is shown. If `None`, it is only shown on
+    /// touch screens and small screens.
+    visible: Option<bool>,
+
+    /// Set once a touch event has been received.
+    #[serde(skip)]
+    touch_screen: bool,
+
+    /// The positions of the current touches by touch id, so that multiple
+    /// keys can be held at once.
+    #[serde(skip)]
+    touches: HashMap<u64, Pos2>,
+}
+
+impl TouchKeypad {
+    /// Draw the keypad in a panel at the bottom of the screen, if it is visible.
+    ///
+    /// Returns whether each Chip8 key is held down on the keypad, indexed by key code.
+    fn update(&mut self, ctx: &Context) -> [bool; 16] {
+        ctx.input(|input| {
+            for event in &input.events {
+                if let egui::Event::Touch { id, phase, pos, .. } = event {
+                    self.touch_screen = true;
+                    match phase {
+                        egui::TouchPhase::Start | egui::TouchPhase::Move => {
+                            self.touches.insert(id.0, *pos);
+                        }
+                        egui::TouchPhase::End | egui::TouchPhase::Cancel => {
+                            self.touches.remove(&id.0);
+                        }
+                    }
+                }
+            }
+        });
+
+        let mut held = [false; 16];
+        if !self.is_visible(ctx) {
+            return held;
+        }
+
+        egui::TopBottomPanel::bottom("touch_keypad").show(ctx, |ui| {
+            egui::Grid::new("touch_keypad_grid").show(ui, |ui| {
+                // the default key map is listed in keypad order
+                for row in DEFAULT_KEY_MAP.chunks(4) {
+                    for &(_, key_code) in row {
+                        let text = egui::RichText::new(format!("{key_code:X}")).heading();
+                        let button = egui::Button::new(text).min_size(egui::vec2(48.0, 48.0));
+                        let response = ui.add(button);
+
+                        // the mouse (and the first touch, which egui treats as the mouse)
+                        // is checked through the response, any other touches by position
+                        held[usize::from(key_code)] = response.is_pointer_button_down_on()
+                            || self
+                                .touches
+                                .values()
+                                .any(|pos| response.rect.contains(*pos));
+                    }
+                    ui.end_row();
+                }
+            });
+        });
+
+        held
+    }
+
+    /// Whether the keypad should be shown, see [`TouchKeypad::visible`].
+    fn is_visible(&self, ctx: &Context) -> bool {
+        self.visible
+            .unwrap_or_else(|| self.touch_screen || ctx.screen_rect().width() < SMALL_SCREEN_WIDTH)
+    }
+
+    /// Toggle the visibility of this `TouchKeypad`.
+    fn toggle_visibility(&mut self, ctx: &Context) {
+        self.visible = Some(!self.is_visible(ctx));
+    }
+}
+
+/// A window showing the size and hash of the loaded ROM.
+#[derive(Default, Deserialize, Serialize)]
+struct RomInfoWindow {
+    visible: bool,
+}
+
+impl RomInfoWindow {
+    fn toggle_visibility(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Draw the window for a ROM of `size` bytes with the CRC32 hash `hash`.
+    fn view(&mut self, ctx: &Context, size: usize, hash: u32) {
+        egui::Window::new("ROM Info")
+            .open(&mut self.visible)
+            .show(ctx, |ui| {
+                egui::Grid::new("rom_info_grid")
+                    .num_columns(2)
+                    .show(ui, |ui| {
+                        ui.label("Size");
+                        ui.label(format!("{size} bytes"));
+                        ui.end_row();
+
+                        ui.label("CRC32");
+                        ui.horizontal(|ui| {
+                            let hash = format!("{hash:08x}");
+                            ui.monospace(&hash);
+                            if ui.small_button("\u{1F4CB}").on_hover_text("Copy").clicked() {
+                                ui.output_mut(|output| output.copied_text = hash);
+                            }
+                        });
+                        ui.end_row();
+                    });
+            });
+    }
+}
+
+/// A small overlay showing the FPS and the measured `Chip8` speed.
+#[derive(Default, Deserialize, Serialize)]
+struct PerformanceOverlay {
+    visible: bool,
+
+    /// The performance the text was last formatted for.
+    #[serde(skip)]
+    shown: Performance,
+
+    /// The formatted text, only rebuilt when the performance changes.
+    #[serde(skip)]
+    text: String,
+}
+
+impl PerformanceOverlay {
+    fn toggle_visibility(&mut self) {
+        self.visible = !self.visible;
+    }
+
+    /// Draw the overlay in the top right corner, if it's visible.
+    fn update(&mut self, ctx: &Context, performance: Performance) {
+        if !self.visible {
+            return;
+        }
+
+        if self.text.is_empty() || self.shown != performance {
+            self.shown = performance;
+            self.text.clear();
+            let _ = write!(
+                self.text,
+                "{:.0} FPS\n{:.0} cycles/s",
+                performance.fps, performance.cycles_per_second
+            );
         }
+
+        egui::Area::new("performance_overlay")
+            .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 32.0))
+            .order(egui::Order::Foreground)
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.label(egui::RichText::new(self.text.as_str()).monospace());
+                });
+            });
     }
 }
 
-impl ConfigWindow {
-    /// Update and render the `ConfigWindow` to the given `Context`.
-    /// This will append any GUI messages to `messages` if the `Chip8` state should be updated.
-    fn update(&mut self, ctx: &Context, messages: &mut mpsc::Sender<Chip8Message>) {
-        egui::Window::new("Config")
-            .open(&mut self.visible)
-            .show(ctx, |ui| {
-                egui::Grid::new("config_grid").show(ui, |ui| {
-                    // foreground color selector
-                    ui.label("Foreground Color");
-                    if ui
-                        .color_edit_button_srgba(&mut self.foreground_rgb)
-                        .changed()
-                    {
-                        let _ = messages.send(Chip8Message::SetForegroundColor(self.foreground_rgb));
-                    }
-                    ui.end_row();
+/// A binding that is waiting for the next input to be bound to it.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Capture {
+    /// Waiting for a key press to bind to the given Chip8 key code.
+    Key(u8),
 
-                    // background color selector
-                    ui.label("Background Color");
-                    if ui
-                        .color_edit_button_srgba(&mut self.background_rgb)
-                        .changed()
-                    {
-                        let _ = messages.send(Chip8Message::SetBackgroundColor(self.background_rgb));
-                    }
-                    ui.end_row();
+    /// Waiting for a gamepad button press to bind to the given Chip8 key code.
+    #[cfg(not(target_arch = "wasm32"))]
+    Button(u8),
+}
 
-                    // step rate selector
-                    ui.label("Steps Per Frame");
-                    let drag = egui::DragValue::new(&mut self.steps_per_frame);
-                    if ui.add(drag).changed() {
-                        let _ = messages.send(Chip8Message::SetStepRate(self.steps_per_frame));
+/// A window that allows the user to rebind the keyboard keys (and gamepad
+/// buttons on native targets) mapped to each Chip8 key.
+#[derive(Default, Deserialize, Serialize)]
+struct KeyBindingsWindow {
+    visible: bool,
+    bindings: KeyBindings,
+    #[cfg(not(target_arch = "wasm32"))]
+    buttons: ButtonBindings,
+
+    #[serde(skip)]
+    capturing: Option<Capture>,
+}
+
+impl KeyBindingsWindow {
+    /// Update and render the `KeyBindingsWindow` to the given `Context`.
+    ///
+    /// `pressed_button` is the last gamepad button pressed this frame, if any.
+    fn update(
+        &mut self,
+        ctx: &Context,
+        #[cfg(not(target_arch = "wasm32"))] pressed_button: Option<Button>,
+    ) {
+        if let Some(capture) = self.capturing {
+            let pressed = ctx.input(|input| {
+                input.events.iter().find_map(|event| match event {
+                    egui::Event::Key {
+                        key, pressed: true, ..
+                    } => Some(*key),
+                    _ => None,
+                })
+            });
+
+            match (capture, pressed) {
+                (_, Some(Key::Escape)) => self.capturing = None,
+                (Capture::Key(key_code), Some(key)) => {
+                    self.bindings.0[usize::from(key_code)] = key;
+                    self.capturing = None;
+                }
+                #[cfg(not(target_arch = "wasm32"))]
+                (Capture::Button(key_code), _) => {
+                    if let Some(button) = pressed_button {
+                        self.buttons.set(key_code, button);
+                        self.capturing = None;
                     }
-                    ui.end_row();
+                }
+                _ => {}
+            }
+        }
 
-                    ui.label("Enable Shift Quirk");
-                    let shift_quirk_checkbox = ui.checkbox(&mut self.shift_quirk_enabled, "");
-                    if shift_quirk_checkbox.changed() {
-                        let _ = messages.send(Chip8Message::SetShiftQuirk(self.shift_quirk_enabled));
+        egui::Window::new("Key Bindings")
+            .open(&mut self.visible)
+            .show(ctx, |ui| {
+                egui::Grid::new("key_bindings_grid").show(ui, |ui| {
+                    // the default key map is listed in keypad order
+                    for row in DEFAULT_KEY_MAP.chunks(4) {
+                        for &(_, key_code) in row {
+                            ui.label(format!("{key_code:X}"));
+                            let text = if self.capturing == Some(Capture::Key(key_code)) {
+                                "Press a key..."
+                            } else {
+                                self.bindings.0[usize::from(key_code)].name()
+                            };
+                            if ui.button(text).clicked() {
+                                self.capturing = Some(Capture::Key(key_code));
+                            }
+
+                            #[cfg(not(target_arch = "wasm32"))]
+                            {
+                                let text = if self.capturing == Some(Capture::Button(key_code)) {
+                                    "Press a button...".to_owned()
+                                } else {
+                                    self.buttons
+                                        .get(key_code)
+                                        .map_or_else(|| "None".to_owned(), |b| format!("{b:?}"))
+                                };
+                                if ui.button(text).clicked() {
+                                    self.capturing = Some(Capture::Button(key_code));
+                                }
+                            }
+                        }
+                        ui.end_row();
                     }
-                    shift_quirk_checkbox.on_hover_text(
-                        "Enable/disable the shift quirk in the interpreter. \
-                        Try toggling this if a program isn't working as expected.",
+                });
+
+                let hotkeys: Vec<_> = self.bindings.hotkeys().map(Key::name).collect();
+                if !hotkeys.is_empty() {
+                    ui.colored_label(
+                        ui.visuals().warn_fg_color,
+                        format!("Disabled hotkeys: {}", hotkeys.join(", ")),
+                    )
+                    .on_hover_text(
+                        "These keys are bound to a Chip8 key, so they only reach the game",
                     );
-                    ui.end_row();
+                }
 
-                    ui.label("Enable VBLANK Wait");
-                    let vblank_wait_checkbox = ui.checkbox(&mut self.vblank_wait_enabled, "");
-                    if vblank_wait_checkbox.changed() {
-                        let _ = messages.send(Chip8Message::SetVblankWait(self.vblank_wait_enabled));
+                ui.separator();
+
+                if ui.button("Reset to Default").clicked() {
+                    self.bindings = KeyBindings::default();
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        self.buttons = ButtonBindings::default();
                     }
-                    vblank_wait_checkbox.on_hover_text(
-                        "Enable/disable waiting for the vertical blank interrupt before drawing a sprite. \
-                        This will limit the amount of sprite draw calls to 60 calls per second."
-                    );
-                    ui.end_row();
-                });
+                    self.capturing = None;
+                }
             });
+
+        if !self.visible {
+            self.capturing = None;
+        }
     }
 
-    /// Push both foreground and background color update messages to `messages`.
-    fn push_color_messages(&self, messages: &mut mpsc::Sender<Chip8Message>) {
-        let _ = messages.send(Chip8Message::SetForegroundColor(self.foreground_rgb));
-        let _ = messages.send(Chip8Message::SetBackgroundColor(self.background_rgb));
+    /// Returns `true` while waiting for a key or button press to bind.
+    fn is_capturing(&self) -> bool {
+        self.capturing.is_some()
     }
 
-    /// Toggle the visibility of this `ConfigWindow`,
+    /// Toggle the visibility of this `KeyBindingsWindow`.
     fn toggle_visibility(&mut self) {
         self.visible = !self.visible;
     }
 }
 
 mod windows {
-    use std::sync::atomic::Ordering;
+    use std::{
+        collections::BTreeSet,
+        sync::{atomic::Ordering, mpsc},
+    };
 
     use chip8::Chip8;
-    use egui::{Context, Ui};
+    use egui::{Color32, Context, Key, RichText, Ui};
     use serde::{Deserialize, Serialize};
 
-    use super::ScreenView;
+    use super::{Chip8Message, DrawStats, ScreenOptions, ScreenView};
+    use crate::disassembler::{self, FONT_SIZE, MEMORY_SIZE, PROGRAM_START};
+
+    /// The amount of bytes shown on each row of the `MemoryWindow`.
+    const BYTES_PER_ROW: usize = 16;
+
+    /// The highlight used for the bytes at the index register in the `MemoryWindow`.
+    const I_HIGHLIGHT: Color32 = Color32::from_rgba_premultiplied(96, 60, 0, 96);
+
+    /// The highlight used for the bytes at the program counter in the `MemoryWindow`.
+    const PC_HIGHLIGHT: Color32 = Color32::from_rgba_premultiplied(0, 45, 96, 96);
+
+    /// The text color of the font bytes in the `MemoryWindow`.
+    const FONT_COLOR: Color32 = Color32::from_rgb(200, 140, 230);
+
+    /// The text color of the rest of the interpreter area in the `MemoryWindow`.
+    const RESERVED_COLOR: Color32 = Color32::from_rgb(128, 128, 128);
+
+    /// The text color of the program area in the `MemoryWindow`.
+    const PROGRAM_COLOR: Color32 = Color32::from_rgb(140, 210, 140);
+
+    /// The text color of the memory region `address` is in.
+    fn region_color(address: usize) -> Color32 {
+        if address < FONT_SIZE {
+            FONT_COLOR
+        } else if address < PROGRAM_START {
+            RESERVED_COLOR
+        } else {
+            PROGRAM_COLOR
+        }
+    }
+
+    /// Parse a hexadecimal address, with or without a leading `0x`.
+    fn parse_address(text: &str) -> Option<usize> {
+        let text = text.trim();
+        let digits = text
+            .strip_prefix("0x")
+            .or_else(|| text.strip_prefix("0X"))
+            .unwrap_or(text);
+        usize::from_str_radix(digits, 16).ok()
+    }
+
+    /// The amount of registers tracked by the `RegistersWindow`: V0-VF, I, PC and SP.
+    const TRACKED_REGISTERS: usize = 19;
+
+    /// The highlight used for registers that recently changed in the `RegistersWindow`.
+    const CHANGE_HIGHLIGHT: Color32 = Color32::from_rgba_premultiplied(0, 96, 30, 128);
+
+    /// The time it takes the highlight of a changed register to fade out, in seconds.
+    const CHANGE_FADE_TIME: f64 = 1.0;
 
     #[derive(Default, Deserialize, Serialize)]
-    pub struct ResgistersWindow {
+    pub struct RegistersWindow {
         visible: bool,
+
+        /// The register values seen on the previous frame, used to find the changed registers.
+        #[serde(skip)]
+        previous: Option<[usize; TRACKED_REGISTERS]>,
+
+        /// The time each register last changed at.
+        #[serde(skip)]
+        changed_at: [Option<f64>; TRACKED_REGISTERS],
     }
 
-    impl ResgistersWindow {
+    impl RegistersWindow {
         pub fn toggle_visibility(&mut self) {
             self.visible = !self.visible;
         }
 
-        /// Draw a window that shows every register in the given `Chip8`.
-        pub fn view(&mut self, ctx: &Context, chip8: &Chip8) {
+        /// The values of every tracked register, V0-VF followed by I, PC and SP.
+        fn register_values(chip8: &Chip8) -> [usize; TRACKED_REGISTERS] {
+            let processor = &chip8.processor;
+            let mut values = [0; TRACKED_REGISTERS];
+            for (value, register) in values.iter_mut().zip(processor.v) {
+                *value = usize::from(register);
+            }
+            values[16] = processor.i;
+            values[17] = processor.pc;
+            values[18] = processor.sp;
+            values
+        }
+
+        /// Compare the registers against the previous frame, and record when they changed.
+        fn track_changes(&mut self, chip8: &Chip8, time: f64) {
+            let values = Self::register_values(chip8);
+            if let Some(previous) = self.previous {
+                for ((changed_at, value), previous) in
+                    self.changed_at.iter_mut().zip(values).zip(previous)
+                {
+                    if value != previous {
+                        *changed_at = Some(time);
+                    }
+                }
+            }
+            self.previous = Some(values);
+        }
+
+        /// The highlight of the register at `index`, fading out as the change gets older.
+        fn highlight(&self, index: usize, time: f64) -> Color32 {
+            let Some(changed_at) = self.changed_at[index] else {
+                return Color32::TRANSPARENT;
+            };
+            let strength = 1.0 - (time - changed_at) / CHANGE_FADE_TIME;
+            if strength <= 0.0 {
+                Color32::TRANSPARENT
+            } else {
+                CHANGE_HIGHLIGHT.gamma_multiply(strength as f32)
+            }
+        }
+
+        /// Draw the contents of a register cell, tinted with `highlight`.
+        fn cell(ui: &mut Ui, highlight: Color32, add_contents: impl FnOnce(&mut Ui)) {
+            egui::Frame::none()
+                .fill(highlight)
+                .inner_margin(egui::Margin::symmetric(4.0, 0.0))
+                .show(ui, add_contents);
+        }
+
+        /// Draw a window that shows every register in the given `Chip8`,
+        /// highlighting the registers that changed recently.
+        ///
+        /// While `paused`, the registers can be edited, so that edits don't race
+        /// with the interpreter.
+        pub fn view(
+            &mut self,
+            ctx: &Context,
+            chip8: &Chip8,
+            paused: bool,
+            messages: &mut mpsc::Sender<Chip8Message>,
+        ) {
+            let time = ctx.input(|i| i.time);
+            self.track_changes(chip8, time);
+            if !self.visible {
+                return;
+            }
+
+            let highlights: [Color32; TRACKED_REGISTERS] =
+                std::array::from_fn(|index| self.highlight(index, time));
+            if highlights
+                .iter()
+                .any(|&highlight| highlight != Color32::TRANSPARENT)
+            {
+                ctx.request_repaint();
+            }
+
             egui::Window::new("Registers")
                 .open(&mut self.visible)
                 .show(ctx, |ui| {
@@ -633,11 +2864,50 @@ mod windows {
                         .num_columns(2)
                         .show(ui, |ui| {
                             ui.heading("I");
-                            ui.heading(format!("{:#06X}", chip8.processor.i));
+                            Self::cell(ui, highlights[16], |ui| {
+                                if paused {
+                                    let mut i = chip8.processor.i;
+                                    let drag = egui::DragValue::new(&mut i)
+                                        .clamp_range(0..=MEMORY_SIZE - 1)
+                                        .hexadecimal(4, false, true)
+                                        .prefix("0x");
+                                    if ui.add(drag).changed() {
+                                        let _ = messages.send(Chip8Message::SetIndexRegister(i));
+                                    }
+                                } else {
+                                    ui.heading(format!("{:#06X}", chip8.processor.i));
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.heading("PC");
+                            Self::cell(ui, highlights[17], |ui| {
+                                ui.heading(format!("{:#06X}", chip8.processor.pc));
+                            });
+                            ui.end_row();
+
+                            ui.heading("SP");
+                            Self::cell(ui, highlights[18], |ui| {
+                                ui.heading(chip8.processor.sp.to_string());
+                            });
                             ui.end_row();
-                            for (i, register) in chip8.processor.v.iter().enumerate() {
-                                ui.heading(format!("V{i:X}"));
-                                ui.heading(register.to_string());
+
+                            for ((index, register), highlight) in
+                                (0..).zip(chip8.processor.v).zip(highlights)
+                            {
+                                ui.heading(format!("V{index:X}"));
+                                Self::cell(ui, highlight, |ui| {
+                                    if paused {
+                                        let mut value = register;
+                                        if ui.add(egui::DragValue::new(&mut value)).changed() {
+                                            let message =
+                                                Chip8Message::SetRegister { index, value };
+                                            let _ = messages.send(message);
+                                        }
+                                    } else {
+                                        ui.heading(register.to_string());
+                                    }
+                                });
                                 ui.end_row();
                             }
                         })
@@ -679,6 +2949,7 @@ mod windows {
     #[derive(Default, Deserialize, Serialize)]
     pub struct ScreenWindow {
         visible: bool,
+        screen: ScreenView,
     }
 
     impl ScreenWindow {
@@ -686,13 +2957,19 @@ mod windows {
             self.visible = !self.visible;
         }
 
+        /// Fit the display to the window again after zooming it with Ctrl+scroll.
+        pub fn reset_zoom(&mut self) {
+            self.screen.reset_zoom();
+        }
+
         /// Draw a window that displays the `Chip8` graphics state.
         pub fn view(&mut self, ctx: &Context, chip8: &Chip8) {
             egui::Window::new("Screen")
                 .open(&mut self.visible)
                 .default_size(egui::vec2(500.0, 250.0))
                 .show(ctx, |ui| {
-                    ScreenView::draw_chip8_renderer(ui, chip8);
+                    let options = ScreenOptions::default();
+                    self.screen.draw_chip8_renderer(ui, chip8, &options);
                 });
         }
     }
@@ -708,8 +2985,15 @@ mod windows {
         }
 
         /// Draw a window that displays the state of both the delay and sound
-        /// timer of the given `Chip8`.
-        pub fn view(&mut self, ctx: &Context, chip8: &Chip8) {
+        /// timer of the given `Chip8`, along with the amount of instructions executed
+        /// and the sprite draws of the last frame.
+        pub fn view(
+            &mut self,
+            ctx: &Context,
+            chip8: &Chip8,
+            instruction_count: u64,
+            draw_stats: DrawStats,
+        ) {
             egui::Window::new("Timers")
                 .open(&mut self.visible)
                 .show(ctx, |ui| {
@@ -726,6 +3010,17 @@ mod windows {
                                 .load(Ordering::SeqCst)
                                 .to_string(),
                         );
+                        ui.end_row();
+                        ui.heading("Instructions");
+                        ui.heading(instruction_count.to_string());
+                        ui.end_row();
+                        ui.heading("Draws");
+                        ui.heading(draw_stats.draws.to_string())
+                            .on_hover_text("Sprites drawn during the last frame");
+                        ui.end_row();
+                        ui.heading("Collisions");
+                        ui.heading(draw_stats.collisions.to_string())
+                            .on_hover_text("Sprite draws during the last frame that set VF");
                     });
                 });
         }
@@ -799,7 +3094,13 @@ mod windows {
 
         /// Draw a window that shows the instructions executed by the `Chip8`,
         /// in their opcode form as well as a more descriptive readable form.
-        pub fn view(&mut self, ctx: &Context, chip8: &Chip8, paused: bool) {
+        pub fn view(
+            &mut self,
+            ctx: &Context,
+            chip8: &Chip8,
+            paused: bool,
+            breakpoints: &BTreeSet<usize>,
+        ) {
             egui::Window::new("Instructions")
                 .open(&mut self.visible)
                 .show(ctx, |ui| {
@@ -828,7 +3129,12 @@ mod windows {
                                     ui.heading("Description");
                                     ui.end_row();
                                     for instr in &chip8.processor.instructions {
-                                        ui.heading(format!("{:#06X}", instr.address));
+                                        let marker = if breakpoints.contains(&instr.address) {
+                                            "\u{25CF} "
+                                        } else {
+                                            ""
+                                        };
+                                        ui.heading(format!("{marker}{:#06X}", instr.address));
                                         ui.add(egui::Separator::default().vertical());
                                         ui.heading(format!("{:#06X}", instr.opcode));
                                         ui.add(egui::Separator::default().vertical());
@@ -840,6 +3146,300 @@ mod windows {
                 });
         }
     }
+
+    #[derive(Default, Deserialize, Serialize)]
+    pub struct DisassemblyWindow {
+        visible: bool,
+    }
+
+    impl DisassemblyWindow {
+        pub fn toggle_visibility(&mut self) {
+            self.visible = !self.visible;
+        }
+
+        /// Draw a window that shows a static disassembly of the whole program area
+        /// of the given `Chip8`, highlighting the instruction at the program counter.
+        ///
+        /// Jump and call targets are given a label, which is shown next to the
+        /// target and used by the instructions that refer to it.
+        pub fn view(&mut self, ctx: &Context, chip8: &Chip8) {
+            egui::Window::new("Disassembly")
+                .open(&mut self.visible)
+                .show(ctx, |ui| {
+                    let pc = chip8.processor.pc;
+                    let opcode_at = |address: usize| {
+                        usize::from(chip8.bus.memory[address]) << 8
+                            | usize::from(chip8.bus.memory[address + 1])
+                    };
+                    let labels = disassembler::collect_labels(
+                        (PROGRAM_START..MEMORY_SIZE).step_by(2).map(opcode_at),
+                    );
+                    let jump_to_pc = ui.button("Jump to PC").clicked();
+                    ui.separator();
+
+                    let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                    let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false, false]);
+                    if jump_to_pc && pc >= PROGRAM_START {
+                        let row = (pc - PROGRAM_START) / 2;
+                        let offset = row as f32 * (row_height + ui.spacing().item_spacing.y);
+                        scroll_area = scroll_area.vertical_scroll_offset(offset);
+                    }
+
+                    let rows = (MEMORY_SIZE - PROGRAM_START) / 2;
+                    scroll_area.show_rows(ui, row_height, rows, |ui, rows| {
+                        egui::Grid::new("disassembly_grid")
+                            .striped(true)
+                            .num_columns(4)
+                            .show(ui, |ui| {
+                                for row in rows {
+                                    let address = PROGRAM_START + row * 2;
+                                    let opcode = opcode_at(address);
+
+                                    let label = if labels.contains(&address) {
+                                        format!("{}:", disassembler::label(address))
+                                    } else {
+                                        String::new()
+                                    };
+                                    let cells = [
+                                        label,
+                                        format!("{address:#06X}"),
+                                        format!("{opcode:#06X}"),
+                                        disassembler::disassemble_labeled(opcode, &labels),
+                                    ];
+                                    for cell in cells {
+                                        let mut text = egui::RichText::new(cell).monospace();
+                                        if address == pc {
+                                            text = text
+                                                .background_color(ui.visuals().selection.bg_fill)
+                                                .color(ui.visuals().selection.stroke.color);
+                                        }
+                                        ui.label(text);
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                });
+        }
+    }
+
+    #[derive(Default, Deserialize, Serialize)]
+    pub struct MemoryWindow {
+        visible: bool,
+
+        /// Allows editing the interpreter area below `PROGRAM_START`, which holds the font.
+        #[serde(skip)]
+        unlock_reserved: bool,
+
+        /// The address of the byte being edited, and the text entered for it.
+        #[serde(skip)]
+        editing: Option<(usize, String)>,
+    }
+
+    impl MemoryWindow {
+        pub fn toggle_visibility(&mut self) {
+            self.visible = !self.visible;
+        }
+
+        /// Draw a window that shows a hex dump of the memory of the given `Chip8`,
+        /// colored by region, highlighting the bytes at the index register and the
+        /// program counter.
+        ///
+        /// While `paused`, clicking a byte allows editing it. Bytes below `PROGRAM_START`
+        /// can only be edited once unlocked, since overwriting them corrupts the font.
+        pub fn view(
+            &mut self,
+            ctx: &Context,
+            chip8: &Chip8,
+            paused: bool,
+            messages: &mut mpsc::Sender<Chip8Message>,
+        ) {
+            if !paused {
+                self.editing = None;
+            }
+
+            let Self {
+                visible,
+                unlock_reserved,
+                editing,
+            } = self;
+
+            egui::Window::new("Memory").open(visible).show(ctx, |ui| {
+                let i = chip8.processor.i;
+                let pc = chip8.processor.pc;
+
+                let mut jump_to_i = false;
+                ui.horizontal(|ui| {
+                    jump_to_i = ui.button("Jump to I").clicked();
+                    ui.label(RichText::new(" I ").background_color(I_HIGHLIGHT));
+                    ui.label(RichText::new(" PC ").background_color(PC_HIGHLIGHT));
+                    ui.add_enabled(
+                        paused,
+                        egui::Checkbox::new(unlock_reserved, "Unlock 0x000-0x1FF"),
+                    )
+                    .on_hover_text("Allow editing the interpreter area, which contains the font.");
+                });
+                ui.horizontal(|ui| {
+                    let regions = [
+                        ("Font", 0, FONT_SIZE, FONT_COLOR),
+                        ("Reserved", FONT_SIZE, PROGRAM_START, RESERVED_COLOR),
+                        ("Program", PROGRAM_START, MEMORY_SIZE, PROGRAM_COLOR),
+                    ];
+                    for (name, start, end, color) in regions {
+                        ui.label(RichText::new(name).color(color))
+                            .on_hover_text(format!("{start:#05X}-{:#05X}", end - 1));
+                    }
+                });
+                ui.separator();
+
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                let mut scroll_area = egui::ScrollArea::vertical().auto_shrink([false, false]);
+                if jump_to_i {
+                    let row = i / BYTES_PER_ROW;
+                    let offset = row as f32 * (row_height + ui.spacing().item_spacing.y);
+                    scroll_area = scroll_area.vertical_scroll_offset(offset);
+                }
+
+                let rows = MEMORY_SIZE / BYTES_PER_ROW;
+                scroll_area.show_rows(ui, row_height, rows, |ui, rows| {
+                    for row in rows {
+                        let start = row * BYTES_PER_ROW;
+                        ui.horizontal(|ui| {
+                            ui.spacing_mut().item_spacing.x = 4.0;
+                            ui.label(RichText::new(format!("{start:#06X}")).monospace().weak());
+
+                            let mut ascii = String::with_capacity(BYTES_PER_ROW);
+                            for address in start..start + BYTES_PER_ROW {
+                                let byte = chip8.bus.memory[address];
+                                let mut text = RichText::new(format!("{byte:02X}"))
+                                    .monospace()
+                                    .color(region_color(address));
+                                if address == i {
+                                    text = text.background_color(I_HIGHLIGHT);
+                                } else if address == pc || address == pc + 1 {
+                                    text = text.background_color(PC_HIGHLIGHT);
+                                }
+
+                                match editing {
+                                    Some((editing_address, input))
+                                        if *editing_address == address =>
+                                    {
+                                        let response = ui.add(
+                                            egui::TextEdit::singleline(input)
+                                                .char_limit(2)
+                                                .desired_width(row_height)
+                                                .font(egui::TextStyle::Monospace),
+                                        );
+                                        response.request_focus();
+                                        if response.lost_focus() {
+                                            if ui.input(|i| i.key_pressed(Key::Enter)) {
+                                                if let Ok(value) =
+                                                    u8::from_str_radix(input.trim(), 16)
+                                                {
+                                                    let message = Chip8Message::WriteMemory {
+                                                        addr: address,
+                                                        value,
+                                                    };
+                                                    let _ = messages.send(message);
+                                                }
+                                            }
+                                            *editing = None;
+                                        }
+                                    }
+                                    _ => {
+                                        let editable = paused
+                                            && (address >= PROGRAM_START || *unlock_reserved);
+                                        let sense = if editable {
+                                            egui::Sense::click()
+                                        } else {
+                                            egui::Sense::hover()
+                                        };
+                                        let label = egui::Label::new(text).sense(sense);
+                                        if ui.add(label).clicked() {
+                                            *editing = Some((address, format!("{byte:02X}")));
+                                        }
+                                    }
+                                }
+
+                                ascii.push(if byte.is_ascii_graphic() {
+                                    char::from(byte)
+                                } else {
+                                    '.'
+                                });
+                            }
+
+                            ui.label(RichText::new(ascii).monospace());
+                        });
+                    }
+                });
+            });
+        }
+    }
+
+    #[derive(Default, Deserialize, Serialize)]
+    pub struct BreakpointsWindow {
+        visible: bool,
+        #[serde(skip)]
+        address_input: String,
+    }
+
+    impl BreakpointsWindow {
+        pub fn toggle_visibility(&mut self) {
+            self.visible = !self.visible;
+        }
+
+        /// Draw a window that lists every breakpoint, with controls to add and remove them.
+        pub fn view(
+            &mut self,
+            ctx: &Context,
+            breakpoints: &BTreeSet<usize>,
+            messages: &mut mpsc::Sender<Chip8Message>,
+        ) {
+            egui::Window::new("Breakpoints")
+                .open(&mut self.visible)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.label("Address");
+                        let input = ui.text_edit_singleline(&mut self.address_input);
+                        let submitted =
+                            input.lost_focus() && ui.input(|input| input.key_pressed(Key::Enter));
+                        if ui.button("Add").clicked() || submitted {
+                            if let Some(address) = parse_address(&self.address_input) {
+                                let _ = messages.send(Chip8Message::AddBreakpoint(address));
+                                self.address_input.clear();
+                            }
+                        }
+
+                        let run_to = ui
+                            .button("Run To")
+                            .on_hover_text("Run until the program counter reaches the address");
+                        if run_to.clicked() {
+                            if let Some(address) = parse_address(&self.address_input) {
+                                let _ = messages.send(Chip8Message::RunToAddress(address));
+                            }
+                        }
+                    });
+                    ui.separator();
+
+                    if breakpoints.is_empty() {
+                        ui.label("No breakpoints set.");
+                    }
+
+                    egui::Grid::new("breakpoints_grid")
+                        .striped(true)
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            for &address in breakpoints {
+                                ui.heading(format!("{address:#06X}"));
+                                if ui.button("Remove").clicked() {
+                                    let _ = messages.send(Chip8Message::RemoveBreakpoint(address));
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+    }
 }
 
 /// A debug screen showing the details of the underlying state of the `Chip8`,
@@ -850,28 +3450,42 @@ struct DebugView {
     /// whether the instructions window should be drawn with every instruction or not.
     paused: bool,
 
-    registers_window: ResgistersWindow,
+    registers_window: RegistersWindow,
     stack_window: StackWindow,
     screen_window: ScreenWindow,
     timers_window: TimersWindow,
     key_window: KeyWindow,
     instructions_window: InstructionsWindow,
+    breakpoints_window: BreakpointsWindow,
+    disassembly_window: DisassemblyWindow,
+    memory_window: MemoryWindow,
 }
 
 impl DebugView {
-    fn toggle_pause(&mut self) {
-        self.paused = !self.paused;
-    }
-
     /// Update the `DebugView`. This will draw all windows on the given context,
     /// and should be called last.
-    fn update(&mut self, ctx: &Context, chip8: &Chip8) {
-        self.registers_window.view(ctx, chip8);
+    fn update(
+        &mut self,
+        ctx: &Context,
+        state: &AppState<'_>,
+        messages: &mut mpsc::Sender<Chip8Message>,
+    ) {
+        let AppState {
+            chip8, breakpoints, ..
+        } = *state;
+
+        self.registers_window
+            .view(ctx, chip8, self.paused, messages);
         self.stack_window.view(ctx, chip8);
         self.screen_window.view(ctx, chip8);
-        self.timers_window.view(ctx, chip8);
+        self.timers_window
+            .view(ctx, chip8, state.instruction_count, state.draw_stats);
         self.key_window.view(ctx, chip8);
-        self.instructions_window.view(ctx, chip8, self.paused);
+        self.instructions_window
+            .view(ctx, chip8, self.paused, breakpoints);
+        self.breakpoints_window.view(ctx, breakpoints, messages);
+        self.disassembly_window.view(ctx, chip8);
+        self.memory_window.view(ctx, chip8, self.paused, messages);
     }
 }
 
@@ -885,6 +3499,82 @@ fn path(_f: &FileHandle) -> Option<PathBuf> {
     None
 }
 
+/// Ask the user for a ROM file, then load it and add it to the recent ROMs.
+fn open_rom_with_dialog(messages: Sender<Chip8Message>) {
+    execute(async move {
+        if let Some(file) = rfd::AsyncFileDialog::new().pick_file().await {
+            let data = file.read().await;
+
+            let recent_rom = RecentRom::new(&file, &data);
+            let _ = messages.send(Chip8Message::AddRecentRom(recent_rom));
+            send_rom(&file.file_name(), data, &messages);
+        }
+    });
+}
+
+/// Load the `data` of the ROM file `name`, assembling it first if it's Octo source.
+fn send_rom(name: &str, data: Vec<u8>, messages: &Sender<Chip8Message>) {
+    let message = if assembler::is_source_file(name) {
+        Chip8Message::LoadSource(String::from_utf8_lossy(&data).into_owned())
+    } else {
+        Chip8Message::LoadRom(data)
+    };
+    let _ = messages.send(message);
+}
+
+/// Fetch the ROM at `url` in the background, then load it.
+///
+/// Failed requests, such as those blocked by the server's CORS policy, are
+/// logged and reported to the user with a browser alert.
+#[cfg(target_arch = "wasm32")]
+pub fn load_rom_from_url(url: String, messages: Sender<Chip8Message>) {
+    execute(async move {
+        match crate::web::fetch(&url).await {
+            Ok(data) => {
+                let name = url.split(['?', '#']).next().unwrap_or(&url);
+                let name = name.rsplit('/').find(|s| !s.is_empty()).unwrap_or(name);
+                let recent_rom = RecentRom {
+                    name: name.to_owned(),
+                    data: data.clone(),
+                };
+                let _ = messages.send(Chip8Message::AddRecentRom(recent_rom));
+                send_rom(name, data, &messages);
+            }
+            Err(e) => {
+                log::error!("Failed to load ROM from {url}: {e}");
+                if let Some(window) = web_sys::window() {
+                    let _ = window.alert_with_message(&format!("Failed to load ROM: {e}"));
+                }
+            }
+        }
+    });
+}
+
+/// Ask the user where to save a file, then send the message built from the chosen path.
+#[cfg(not(target_arch = "wasm32"))]
+fn save_with_dialog<F>(default_name: &str, messages: Sender<Chip8Message>, message: F)
+where
+    F: FnOnce(PathBuf) -> Chip8Message + Send + 'static,
+{
+    let default_name = default_name.to_owned();
+    execute(async move {
+        let dialog = rfd::AsyncFileDialog::new().set_file_name(default_name);
+        if let Some(file) = dialog.save_file().await {
+            let _ = messages.send(message(file.path().to_path_buf()));
+        }
+    });
+}
+
+/// There is no file system on the web, so the message is sent straight away with
+/// `default_name`, which is then used as the name of the browser download.
+#[cfg(target_arch = "wasm32")]
+fn save_with_dialog<F>(default_name: &str, messages: Sender<Chip8Message>, message: F)
+where
+    F: FnOnce(PathBuf) -> Chip8Message,
+{
+    let _ = messages.send(message(PathBuf::from(default_name)));
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn execute<F: Future<Output = ()> + Send + 'static>(f: F) {
     std::thread::spawn(move || futures_executor::block_on(f));
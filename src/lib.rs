@@ -1,5 +1,18 @@
 mod app;
 pub use app::App;
-#[cfg(not(target_arch = "wasm32"))]
+pub mod assembler;
 pub mod audio;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod cli;
+pub mod disassembler;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod gamepad;
 pub mod gui;
+pub mod recorder;
+pub mod replay;
+pub mod roms;
+pub mod save_state;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod trace;
+#[cfg(target_arch = "wasm32")]
+mod web;
@@ -6,7 +6,11 @@
 fn main() -> eframe::Result<()> {
     // Log to stdout (if you run with `RUST_LOG=debug`).
 
+    use clap::Parser;
     use env_logger::{Builder, Target};
+
+    let args = chip8_ui::cli::Args::parse();
+
     let mut builder = Builder::from_default_env();
 
     builder.target(Target::Stdout);
@@ -16,7 +20,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "Chip8",
         native_options,
-        Box::new(|cc| Box::new(chip8_ui::App::new(cc))),
+        Box::new(move |cc| Box::new(chip8_ui::App::with_args(cc, &args))),
     )
 }
 
@@ -0,0 +1,124 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use gif::{Encoder, Frame, Repeat};
+use serde::{Deserialize, Serialize};
+
+use crate::app::write_file;
+
+/// The options used when recording the `Chip8` display to a GIF.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+pub struct RecordingOptions {
+    /// The factor each frame is upscaled by when encoding.
+    pub scale: u16,
+
+    /// The amount of frames captured per second.
+    pub frame_rate: u16,
+
+    /// The maximum amount of frames kept in memory. Once reached, a warning
+    /// is logged and no more frames are captured.
+    pub max_frames: usize,
+}
+
+impl Default for RecordingOptions {
+    fn default() -> Self {
+        Self {
+            scale: 4,
+            frame_rate: 30,
+            max_frames: 1800,
+        }
+    }
+}
+
+/// Captures frames of the `Chip8` display, and encodes them as an animated GIF.
+pub struct Recorder {
+    options: RecordingOptions,
+    frames: Vec<Vec<u8>>,
+
+    /// Seconds elapsed since the last frame was captured.
+    elapsed: f32,
+}
+
+impl Recorder {
+    /// Create a new [`Recorder`] with no captured frames.
+    #[must_use]
+    pub fn new(options: RecordingOptions) -> Self {
+        Self {
+            options,
+            frames: Vec::new(),
+            elapsed: 0.0,
+        }
+    }
+
+    /// Capture the given RGB display data as a new frame, if enough time has passed
+    /// since the last capture to match the frame rate. `dt` is the amount of seconds
+    /// since this was last called.
+    pub fn capture(&mut self, dt: f32, rgb: &[u8]) {
+        if self.frames.len() >= self.options.max_frames {
+            return;
+        }
+
+        self.elapsed += dt;
+        let frame_interval = 1.0 / f32::from(self.options.frame_rate.max(1));
+        if !self.frames.is_empty() && self.elapsed < frame_interval {
+            return;
+        }
+        self.elapsed = 0.0;
+
+        self.frames.push(rgb.to_vec());
+        if self.frames.len() == self.options.max_frames {
+            log::warn!(
+                "Recording reached the limit of {} frames, no more frames will be captured.",
+                self.options.max_frames
+            );
+        }
+    }
+
+    /// Encode the captured frames and write the GIF to the file specified by `path`.
+    ///
+    /// Encoding happens on a separate thread on native targets, so that rendering is not stalled.
+    pub fn save(self, path: PathBuf) {
+        let save = move || {
+            if let Err(e) = self.encode().and_then(|bytes| write_file(&path, &bytes)) {
+                log::error!("Failed to save recording to {}: {e}.", path.display());
+            }
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        std::thread::spawn(save);
+
+        #[cfg(target_arch = "wasm32")]
+        save();
+    }
+
+    /// Encode the captured frames as an animated GIF.
+    fn encode(&self) -> anyhow::Result<Vec<u8>> {
+        let scale = usize::from(self.options.scale.max(1));
+        let width = chip8::graphics::WIDTH * scale;
+        let height = chip8::graphics::HEIGHT * scale;
+        let gif_width = u16::try_from(width).context("Recording scale is too large.")?;
+        let gif_height = u16::try_from(height).context("Recording scale is too large.")?;
+        let delay = 100 / self.options.frame_rate.clamp(1, 100);
+
+        let mut encoder = Encoder::new(Vec::new(), gif_width, gif_height, &[])?;
+        encoder.set_repeat(Repeat::Infinite)?;
+
+        let mut pixels = Vec::with_capacity(width * height * 3);
+        for rgb in &self.frames {
+            pixels.clear();
+            for y in 0..height {
+                let row = y / scale * chip8::graphics::WIDTH;
+                for x in 0..width {
+                    let index = (row + x / scale) * 3;
+                    pixels.extend_from_slice(&rgb[index..index + 3]);
+                }
+            }
+
+            let mut frame = Frame::from_rgb_speed(gif_width, gif_height, &pixels, 10);
+            frame.delay = delay;
+            encoder.write_frame(&frame)?;
+        }
+
+        Ok(encoder.into_inner()?)
+    }
+}
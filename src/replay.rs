@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+
+/// A change in the pressed `Chip8` keys, recorded at a specific cycle.
+#[derive(Clone, Copy, Deserialize, Serialize)]
+struct InputEvent {
+    /// The amount of cycles executed since the recording started.
+    cycle: u64,
+
+    /// The pressed state of every key, where bit `n` is set if key `n` is pressed.
+    keys: u16,
+}
+
+/// A recording of the `Chip8` keys pressed during a session, which can be
+/// played back to reproduce that session.
+///
+/// The random numbers drawn by `Cxkk` can't be seeded, so a session is only
+/// reproduced if the ROM doesn't use them, see [`Replay::uses_random`].
+#[derive(Deserialize, Serialize)]
+pub struct Replay {
+    /// The CRC32 hash of the ROM the recording was made with.
+    pub rom_hash: u32,
+    events: Vec<InputEvent>,
+
+    /// Set if the ROM drew a random number while recording or playing back,
+    /// in which case the playback can desync from the recorded session.
+    #[serde(default)]
+    pub uses_random: bool,
+}
+
+impl Replay {
+    /// Serialize this [`Replay`] as JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization fails.
+    pub fn to_json(&self) -> anyhow::Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Deserialize a [`Replay`] from JSON.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid replay.
+    pub fn from_json(bytes: &[u8]) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// Records the `Chip8` key state whenever it changes.
+pub struct InputRecorder {
+    replay: Replay,
+    keys: u16,
+}
+
+impl InputRecorder {
+    /// Create a new [`InputRecorder`] for the ROM with the given hash.
+    #[must_use]
+    pub fn new(rom_hash: u32) -> Self {
+        Self {
+            replay: Replay {
+                rom_hash,
+                events: Vec::new(),
+                uses_random: false,
+            },
+            keys: 0,
+        }
+    }
+
+    /// Apply the given key updates, recording the new key state at `cycle` if it changed.
+    pub fn record(&mut self, cycle: u64, key_updates: &[(u8, bool)]) {
+        let mut keys = self.keys;
+        for &(key_code, pressed) in key_updates {
+            let bit = 1 << (key_code & 0xF);
+            if pressed {
+                keys |= bit;
+            } else {
+                keys &= !bit;
+            }
+        }
+
+        if keys != self.keys {
+            self.keys = keys;
+            self.replay.events.push(InputEvent { cycle, keys });
+        }
+    }
+
+    /// Note that the ROM drew a random number, see [`Replay::uses_random`].
+    pub fn record_random(&mut self) {
+        self.replay.uses_random = true;
+    }
+
+    /// Stop recording and return the recorded [`Replay`].
+    #[must_use]
+    pub fn finish(self) -> Replay {
+        self.replay
+    }
+}
+
+/// Plays back the key state of a [`Replay`].
+pub struct ReplayPlayer {
+    replay: Replay,
+    next: usize,
+}
+
+impl ReplayPlayer {
+    /// Create a new [`ReplayPlayer`] starting at the beginning of `replay`.
+    #[must_use]
+    pub fn new(replay: Replay) -> Self {
+        Self { replay, next: 0 }
+    }
+
+    /// The key state that should be applied before executing `cycle`, if it changes
+    /// on that cycle. Key `n` is pressed if bit `n` is set.
+    ///
+    /// Several changes can be recorded on the same cycle, such as while paused,
+    /// so every change up to `cycle` is applied at once and the last one wins.
+    pub fn keys_at(&mut self, cycle: u64) -> Option<u16> {
+        let due = self.replay.events[self.next..]
+            .iter()
+            .take_while(|event| event.cycle <= cycle)
+            .count();
+        if due == 0 {
+            return None;
+        }
+        self.next += due;
+        Some(self.replay.events[self.next - 1].keys)
+    }
+
+    /// The [`Replay`] being played back.
+    #[must_use]
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Note that the ROM drew a random number during playback, see
+    /// [`Replay::uses_random`]. Returns `true` if this wasn't known yet.
+    pub fn record_random(&mut self) -> bool {
+        !std::mem::replace(&mut self.replay.uses_random, true)
+    }
+
+    /// Returns `true` once every recorded key change has been played back.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.replay.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_on_the_same_cycle_play_back_together() {
+        let mut recorder = InputRecorder::new(0);
+        recorder.record(5, &[(0x1, true)]);
+        recorder.record(5, &[(0x2, true)]);
+        recorder.record(5, &[(0x1, false)]);
+        recorder.record(8, &[(0x2, false)]);
+
+        let mut player = ReplayPlayer::new(recorder.finish());
+        assert_eq!(player.keys_at(4), None);
+        assert_eq!(player.keys_at(5), Some(0b100));
+        assert_eq!(player.keys_at(6), None);
+        assert_eq!(player.keys_at(8), Some(0));
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn random_numbers_are_recorded() {
+        let mut recorder = InputRecorder::new(0);
+        recorder.record_random();
+        let json = recorder.finish().to_json().unwrap();
+        assert!(Replay::from_json(&json).unwrap().uses_random);
+
+        // recordings from before random numbers were tracked
+        let old = Replay::from_json(br#"{"rom_hash":0,"events":[]}"#).unwrap();
+        assert!(!old.uses_random);
+
+        let mut player = ReplayPlayer::new(old);
+        assert!(player.record_random());
+        assert!(!player.record_random());
+        assert!(player.replay().uses_random);
+    }
+}
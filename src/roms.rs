@@ -0,0 +1,24 @@
+/// A small test program embedded in the binary, so there is always something to run.
+pub struct BuiltinRom {
+    /// The name shown in the "Load Built-in ROM" menu.
+    pub name: &'static str,
+
+    /// The ROM data itself.
+    pub data: &'static [u8],
+}
+
+/// Every ROM embedded in the binary, see `assets/roms`.
+pub const BUILTIN_ROMS: &[BuiltinRom] = &[
+    BuiltinRom {
+        name: "Font Preview",
+        data: include_bytes!("../assets/roms/font_preview.ch8"),
+    },
+    BuiltinRom {
+        name: "Keypad Test",
+        data: include_bytes!("../assets/roms/keypad_test.ch8"),
+    },
+    BuiltinRom {
+        name: "Bounce",
+        data: include_bytes!("../assets/roms/bounce.ch8"),
+    },
+];
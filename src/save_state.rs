@@ -0,0 +1,169 @@
+use std::sync::atomic::Ordering;
+
+use anyhow::{bail, Context};
+use chip8::Chip8;
+
+/// The bytes every save state starts with.
+const MAGIC: &[u8; 4] = b"C8SV";
+
+/// The version of the save state format written by this version of the app.
+///
+/// This must be increased whenever the serialized layout of [`Chip8`] changes,
+/// so that older save states are detected instead of failing to deserialize.
+///
+/// Version 2 added the hash of the ROM the state was saved with, and version 3
+/// the sound timer, which [`Chip8`] doesn't serialize.
+pub const VERSION: u16 = 3;
+
+/// The length of the magic bytes, version, ROM hash and sound timer that precede the state.
+const HEADER_LEN: usize = MAGIC.len() + std::mem::size_of::<u16>() + std::mem::size_of::<u32>() + 1;
+
+/// A decoded save state.
+pub struct SaveState {
+    pub chip8: Chip8,
+
+    /// The CRC32 hash of the ROM the state was saved with, or `None` if
+    /// the save state is from before the hash was stored.
+    pub rom_hash: Option<u32>,
+
+    /// The value of the sound timer, `0` if the save state is from before it was stored.
+    pub sound_timer: u8,
+}
+
+/// Serialize `chip8` as a versioned save state, made with the ROM with the given hash.
+///
+/// # Errors
+///
+/// Returns an error if `chip8` fails to serialize.
+pub fn encode(chip8: &Chip8, rom_hash: u32) -> anyhow::Result<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(&VERSION.to_le_bytes());
+    bytes.extend_from_slice(&rom_hash.to_le_bytes());
+    bytes.push(chip8.bus.clock.sound_timer.load(Ordering::SeqCst));
+    bincode::serialize_into(&mut bytes, chip8)?;
+    Ok(bytes)
+}
+
+/// Deserialize a [`SaveState`] created by [`encode`].
+///
+/// Save states from before the format was versioned have no header, these are
+/// still loaded as long as the [`Chip8`] layout hasn't changed since.
+///
+/// # Errors
+///
+/// Returns an error if `bytes` isn't a save state, or if it was saved by an
+/// incompatible version of the app.
+pub fn decode(bytes: &[u8]) -> anyhow::Result<SaveState> {
+    let Some(payload) = bytes.strip_prefix(MAGIC) else {
+        let chip8 = bincode::deserialize(bytes)
+            .context("This file is not a save state, or was saved by an unsupported version.")?;
+        return Ok(SaveState {
+            chip8,
+            rom_hash: None,
+            sound_timer: 0,
+        });
+    };
+
+    let [low, high, payload @ ..] = payload else {
+        bail!("The save state is truncated.");
+    };
+    let version = u16::from_le_bytes([*low, *high]);
+
+    match version {
+        VERSION => {
+            let [a, b, c, d, sound_timer, payload @ ..] = payload else {
+                bail!("The save state is truncated.");
+            };
+            Ok(SaveState {
+                chip8: bincode::deserialize(payload)?,
+                rom_hash: Some(u32::from_le_bytes([*a, *b, *c, *d])),
+                sound_timer: *sound_timer,
+            })
+        }
+        2 => {
+            let [a, b, c, d, payload @ ..] = payload else {
+                bail!("The save state is truncated.");
+            };
+            Ok(SaveState {
+                chip8: bincode::deserialize(payload)?,
+                rom_hash: Some(u32::from_le_bytes([*a, *b, *c, *d])),
+                sound_timer: 0,
+            })
+        }
+        1 => Ok(SaveState {
+            chip8: bincode::deserialize(payload)?,
+            rom_hash: None,
+            sound_timer: 0,
+        }),
+        version if version > VERSION => {
+            bail!("The save state is from a newer version (format {version}) of the app.")
+        }
+        version => bail!("The save state format {version} is no longer supported."),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut chip8 = Chip8::new();
+        chip8.processor.pc = 0x2A4;
+        chip8.processor.v[0x3] = 0x7F;
+        chip8.bus.memory[0x300] = 0xAB;
+        chip8.bus.clock.sound_timer.store(42, Ordering::SeqCst);
+
+        let state = decode(&encode(&chip8, 0xDEAD_BEEF).unwrap()).unwrap();
+
+        assert_eq!(state.rom_hash, Some(0xDEAD_BEEF));
+        assert_eq!(state.sound_timer, 42);
+        assert_eq!(state.chip8.processor.pc, 0x2A4);
+        assert_eq!(state.chip8.processor.v[0x3], 0x7F);
+        assert_eq!(state.chip8.bus.memory[0x300], 0xAB);
+    }
+
+    #[test]
+    fn rejects_newer_version() {
+        let mut bytes = encode(&Chip8::new(), 0).unwrap();
+        bytes[MAGIC.len()..MAGIC.len() + 2].copy_from_slice(&(VERSION + 1).to_le_bytes());
+
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        assert!(decode(b"C8SV\x02").is_err());
+    }
+
+    #[test]
+    fn decodes_version_1_without_rom_hash() {
+        let mut chip8 = Chip8::new();
+        chip8.processor.pc = 0x246;
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bincode::serialize_into(&mut bytes, &chip8).unwrap();
+        let state = decode(&bytes).unwrap();
+
+        assert_eq!(state.rom_hash, None);
+        assert_eq!(state.chip8.processor.pc, 0x246);
+    }
+
+    #[test]
+    fn decodes_version_2_without_sound_timer() {
+        let mut chip8 = Chip8::new();
+        chip8.processor.pc = 0x246;
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&2u16.to_le_bytes());
+        bytes.extend_from_slice(&0x1234_5678u32.to_le_bytes());
+        bincode::serialize_into(&mut bytes, &chip8).unwrap();
+        let state = decode(&bytes).unwrap();
+
+        assert_eq!(state.rom_hash, Some(0x1234_5678));
+        assert_eq!(state.sound_timer, 0);
+        assert_eq!(state.chip8.processor.pc, 0x246);
+    }
+}
@@ -0,0 +1,71 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use chip8::Chip8;
+
+use crate::disassembler::MEMORY_SIZE;
+
+/// Writes a line for every instruction the `Chip8` executes to a file, with the
+/// full register state before it runs, so that runs can be diffed against the
+/// traces of other emulators.
+pub struct Tracer {
+    writer: BufWriter<File>,
+}
+
+impl Tracer {
+    /// Create a new [`Tracer`] that writes to the file at `path`, replacing it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be created.
+    pub fn create(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Ok(Self {
+            writer: BufWriter::new(File::create(path)?),
+        })
+    }
+
+    /// Write the instruction at the program counter of `chip8`, which is about to
+    /// be executed, along with the current registers and timers. For example:
+    ///
+    /// `PC:0200 OP:6A02 V:00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00 I:0000 SP:00 DT:00 ST:00`
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the file fails.
+    pub fn trace(&mut self, chip8: &Chip8) -> std::io::Result<()> {
+        let processor = &chip8.processor;
+        let memory = &chip8.bus.memory;
+        let clock = &chip8.bus.clock;
+
+        let pc = processor.pc;
+        let opcode = if pc + 1 < MEMORY_SIZE {
+            u16::from_be_bytes([memory[pc], memory[pc + 1]])
+        } else {
+            0
+        };
+
+        write!(self.writer, "PC:{pc:04X} OP:{opcode:04X} V:")?;
+        for register in processor.v {
+            write!(self.writer, "{register:02X} ")?;
+        }
+        writeln!(
+            self.writer,
+            "I:{:04X} SP:{:02X} DT:{:02X} ST:{:02X}",
+            processor.i,
+            processor.sp,
+            clock.delay_timer,
+            clock.sound_timer.load(Ordering::SeqCst)
+        )
+    }
+
+    /// Write any buffered lines to the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if writing to the file fails.
+    pub fn finish(mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
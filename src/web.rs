@@ -0,0 +1,116 @@
+use anyhow::{anyhow, bail, Context};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+
+/// How long to keep the object URL of a download alive after clicking its link,
+/// in milliseconds. Firefox and Safari cancel the download if it is revoked
+/// in the same tick as the click.
+const REVOKE_DELAY_MS: i32 = 1000;
+
+/// Trigger a browser download of `bytes` as a file named `file_name`.
+///
+/// # Errors
+///
+/// Returns an error if the browser window or document cannot be accessed,
+/// or if any of the DOM calls used to create the download fail.
+pub fn download(file_name: &str, bytes: &[u8]) -> anyhow::Result<()> {
+    let window = web_sys::window().context("Failed to access the browser window.")?;
+    let document = window
+        .document()
+        .context("Failed to access the browser document.")?;
+
+    let parts = js_sys::Array::of1(&js_sys::Uint8Array::from(bytes));
+    let blob = web_sys::Blob::new_with_u8_array_sequence(&parts).map_err(js_error)?;
+    let url = web_sys::Url::create_object_url_with_blob(&blob).map_err(js_error)?;
+
+    let anchor = document
+        .create_element("a")
+        .map_err(js_error)?
+        .dyn_into::<web_sys::HtmlAnchorElement>()
+        .map_err(|_| anyhow!("Failed to create a download link."))?;
+    anchor.set_href(&url);
+    anchor.set_download(file_name);
+    anchor.click();
+
+    let revoke = Closure::once_into_js(move || {
+        if let Err(e) = web_sys::Url::revoke_object_url(&url) {
+            log::warn!("Failed to revoke download URL: {e:?}");
+        }
+    });
+    window
+        .set_timeout_with_callback_and_timeout_and_arguments_0(
+            revoke.unchecked_ref(),
+            REVOKE_DELAY_MS,
+        )
+        .map_err(js_error)?;
+    Ok(())
+}
+
+/// Fetch the contents of `url` as raw bytes.
+///
+/// # Errors
+///
+/// Returns an error if the browser window cannot be accessed, if the request
+/// fails (for example because the server doesn't allow cross-origin requests),
+/// or if the server responds with an error status.
+pub async fn fetch(url: &str) -> anyhow::Result<Vec<u8>> {
+    let window = web_sys::window().context("Failed to access the browser window.")?;
+
+    let response = JsFuture::from(window.fetch_with_str(url))
+        .await
+        .map_err(js_error)?
+        .dyn_into::<web_sys::Response>()
+        .map_err(|_| anyhow!("Failed to read the response from {url}."))?;
+    if !response.ok() {
+        bail!(
+            "Failed to fetch {url}: {} {}",
+            response.status(),
+            response.status_text()
+        );
+    }
+
+    let buffer = JsFuture::from(response.array_buffer().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    Ok(js_sys::Uint8Array::new(&buffer).to_vec())
+}
+
+/// Read the query parameter `name` from the page URL, such as `rom` in `?rom=pong.ch8`.
+#[must_use]
+pub fn query_param(name: &str) -> Option<String> {
+    let search = web_sys::window()?.location().search().ok()?;
+    web_sys::UrlSearchParams::new_with_str(&search)
+        .ok()?
+        .get(name)
+}
+
+/// The id of the canvas the app is drawn on, see `main.rs`.
+const CANVAS_ID: &str = "the_canvas_id";
+
+/// Make the app canvas fullscreen, or leave fullscreen if the page already is.
+///
+/// # Errors
+///
+/// Returns an error if the document or canvas cannot be accessed, or if the
+/// browser refuses the request.
+pub fn toggle_fullscreen() -> anyhow::Result<()> {
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .context("Failed to access the browser document.")?;
+
+    if document.fullscreen_element().is_some() {
+        document.exit_fullscreen();
+        return Ok(());
+    }
+
+    document
+        .get_element_by_id(CANVAS_ID)
+        .context("Failed to find the app canvas.")?
+        .request_fullscreen()
+        .map_err(js_error)
+}
+
+/// Convert a JavaScript exception into an [`anyhow::Error`].
+pub(crate) fn js_error(value: JsValue) -> anyhow::Error {
+    anyhow!("{value:?}")
+}